@@ -12,19 +12,18 @@ const POLYGON_TOKENS: &str = include_str!("../../evm_tokens/polygon_tokens.json"
 
 use crate::{
     logs::INFO,
-    state::{mutate_state, Erc20Identifier, EvmToken},
+    state::{mutate_state, EvmToken},
 };
 
+// Runs on init and on every upgrade, tokens added, updated, hidden or deleted at runtime are
+// left as they are
 pub fn add_evm_tokens_to_state() {
+    let merged = mutate_state(|s| s.merge_bundled_evm_tokens(deserialize_all_tokens()));
     log!(
         INFO,
-        "[Add EVM Tokens] Adding new EVM tokens from json files",
+        "[Add EVM Tokens] Merged {} EVM tokens from json files",
+        merged
     );
-    mutate_state(|s| {
-        deserialize_all_tokens()
-            .into_iter()
-            .for_each(|token| s.record_evm_token(Erc20Identifier::from(&token), token))
-    });
 }
 
 pub fn deserialize_all_tokens() -> Vec<EvmToken> {
@@ -47,6 +46,11 @@ pub fn deserialize_json_into_evm_token(json_str: &str) -> Vec<EvmToken> {
     serde_json::from_str(json_str).unwrap()
 }
 
+// Parses a token list in the format of the bundled json files
+pub fn parse_evm_tokens_json(json_str: &str) -> Result<Vec<EvmToken>, String> {
+    serde_json::from_str(json_str).map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,394 @@
+// Transfer statistics aggregated per day, operator and token
+// Transfers are counted in the bucket of the day they were accepted, so their completion or
+// failure updates the same bucket and rates stay consistent within a window
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::{
+    CandidBridgeStats, CandidDailyUsdVolume, CandidTransferStats, CandidTransferUsdValue,
+    CandidUsdVolume, StatsWindow,
+};
+use crate::numeric::{Erc20TokenAmount, UsdPrice, UsdValue, USD_DECIMALS};
+use crate::state::{Erc20Identifier, Operator};
+
+pub(crate) const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+pub(crate) const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+// Upper bounds in seconds of the latency histogram buckets, longer latencies are counted in an
+// overflow bucket
+const LATENCY_BOUNDS_SECONDS: [u64; 24] = [
+    5, 10, 15, 20, 30, 45, 60, 90, 120, 180, 300, 450, 600, 900, 1_200, 1_800, 2_700, 3_600, 5_400,
+    7_200, 10_800, 21_600, 43_200, 86_400,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BridgeDirection {
+    EvmToIcp,
+    IcpToEvm,
+}
+
+// Day since the unix epoch, operator and token of a bucket
+#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct BridgeStatsKey(pub u64, pub Operator, pub Erc20Identifier);
+
+impl BridgeStatsKey {
+    pub fn new(accepted_at: u64, operator: Operator, token: Erc20Identifier) -> Self {
+        Self(day_of(accepted_at), operator, token)
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct BridgeStatsBucket {
+    pub deposits: TransferStats,
+    pub withdrawals: TransferStats,
+}
+
+impl BridgeStatsBucket {
+    pub fn direction_mut(&mut self, direction: BridgeDirection) -> &mut TransferStats {
+        match direction {
+            BridgeDirection::EvmToIcp => &mut self.deposits,
+            BridgeDirection::IcpToEvm => &mut self.withdrawals,
+        }
+    }
+
+    fn merge(&mut self, other: BridgeStatsBucket) {
+        self.deposits.merge(other.deposits);
+        self.withdrawals.merge(other.withdrawals);
+    }
+}
+
+// Latencies are in nanoseconds, from acceptance to mint or successful finalization
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TransferStats {
+    pub count: u64,
+    pub volume: Erc20TokenAmount,
+    pub completed: u64,
+    pub failed: u64,
+    pub latencies: LatencyHistogram,
+}
+
+impl Default for TransferStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            volume: Erc20TokenAmount::ZERO,
+            completed: 0,
+            failed: 0,
+            latencies: LatencyHistogram::default(),
+        }
+    }
+}
+
+impl TransferStats {
+    pub fn record_accepted(&mut self, amount: Erc20TokenAmount) {
+        self.count += 1;
+        self.volume = self
+            .volume
+            .checked_add(amount)
+            .unwrap_or(Erc20TokenAmount::MAX);
+    }
+
+    pub fn record_completed(&mut self, latency: u64) {
+        self.completed += 1;
+        self.latencies.record(latency);
+    }
+
+    pub fn record_failed(&mut self) {
+        self.failed += 1;
+    }
+
+    fn merge(&mut self, other: TransferStats) {
+        self.count += other.count;
+        self.volume = self
+            .volume
+            .checked_add(other.volume)
+            .unwrap_or(Erc20TokenAmount::MAX);
+        self.completed += other.completed;
+        self.failed += other.failed;
+        self.latencies.merge(&other.latencies);
+    }
+}
+
+// Latencies counted in fixed buckets, so buckets of any number of days merge in constant size
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LatencyHistogram {
+    counts: [u64; LATENCY_BOUNDS_SECONDS.len() + 1],
+    max_latency: u64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: u64) {
+        let bucket = LATENCY_BOUNDS_SECONDS
+            .iter()
+            .position(|bound| latency <= bound * NANOS_PER_SECOND)
+            .unwrap_or(LATENCY_BOUNDS_SECONDS.len());
+        self.counts[bucket] += 1;
+        self.max_latency = self.max_latency.max(latency);
+    }
+
+    fn merge(&mut self, other: &LatencyHistogram) {
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts) {
+            *count += other_count;
+        }
+        self.max_latency = self.max_latency.max(other.max_latency);
+    }
+
+    // Nearest rank percentile, reported as the upper bound of the bucket holding the rank
+    // and capped by the longest recorded latency
+    fn percentile(&self, percentile: u64) -> Option<u64> {
+        let total: u64 = self.counts.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let rank = (total * percentile).div_ceil(100).max(1);
+
+        let mut seen = 0;
+        let bucket = self.counts.iter().position(|count| {
+            seen += count;
+            seen >= rank
+        })?;
+        let upper_bound = LATENCY_BOUNDS_SECONDS
+            .get(bucket)
+            .map_or(self.max_latency, |bound| bound * NANOS_PER_SECOND);
+        Some(upper_bound.min(self.max_latency))
+    }
+}
+
+impl From<TransferStats> for CandidTransferStats {
+    fn from(value: TransferStats) -> Self {
+        let failure_rate = if value.count == 0 {
+            0.0
+        } else {
+            value.failed as f64 / value.count as f64
+        };
+
+        Self {
+            count: value.count,
+            volume: value.volume.into(),
+            completed: value.completed,
+            failed: value.failed,
+            failure_rate,
+            median_latency_seconds: value
+                .latencies
+                .percentile(50)
+                .map(|latency| latency / NANOS_PER_SECOND),
+            p95_latency_seconds: value
+                .latencies
+                .percentile(95)
+                .map(|latency| latency / NANOS_PER_SECOND),
+        }
+    }
+}
+
+// Usd value of a transfer when it was accepted, together with the twin token price it was valued at
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TransferUsdValue {
+    pub usd_price: UsdPrice,
+    pub usd_value: UsdValue,
+}
+
+impl TransferUsdValue {
+    // Values an amount in the smallest units of a token with its price per whole token
+    // Transfers of tokens without a known positive price are not valued
+    pub fn new(amount: Erc20TokenAmount, decimals: u8, usd_price: UsdPrice) -> Option<Self> {
+        if usd_price == UsdPrice::ZERO {
+            return None;
+        }
+        Some(Self {
+            usd_price,
+            usd_value: usd_price.checked_value_of(amount, decimals)?,
+        })
+    }
+}
+
+impl From<TransferUsdValue> for CandidTransferUsdValue {
+    fn from(value: TransferUsdValue) -> Self {
+        Self {
+            usd_price: value.usd_price.to_decimal_string(USD_DECIMALS),
+            usd_value: value.usd_value.to_decimal_string(USD_DECIMALS),
+            usd_price_e18: value.usd_price.into(),
+            usd_value_e18: value.usd_value.into(),
+        }
+    }
+}
+
+// Usd volume of the transfers accepted on a day, keyed like the bridge stats buckets
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct UsdVolumeBucket {
+    pub deposits: UsdVolume,
+    pub withdrawals: UsdVolume,
+}
+
+impl UsdVolumeBucket {
+    pub fn direction_mut(&mut self, direction: BridgeDirection) -> &mut UsdVolume {
+        match direction {
+            BridgeDirection::EvmToIcp => &mut self.deposits,
+            BridgeDirection::IcpToEvm => &mut self.withdrawals,
+        }
+    }
+}
+
+// Only transfers that could be valued are counted
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct UsdVolume {
+    pub transfers: u64,
+    pub usd_value: UsdValue,
+}
+
+impl Default for UsdVolume {
+    fn default() -> Self {
+        Self {
+            transfers: 0,
+            usd_value: UsdValue::ZERO,
+        }
+    }
+}
+
+impl UsdVolume {
+    pub fn record(&mut self, usd_value: UsdValue) {
+        self.transfers += 1;
+        self.usd_value = self
+            .usd_value
+            .checked_add(usd_value)
+            .unwrap_or(UsdValue::MAX);
+    }
+}
+
+impl From<UsdVolume> for CandidUsdVolume {
+    fn from(value: UsdVolume) -> Self {
+        Self {
+            transfers: value.transfers,
+            usd_value: value.usd_value.to_decimal_string(USD_DECIMALS),
+            usd_value_e18: value.usd_value.into(),
+        }
+    }
+}
+
+impl From<(BridgeStatsKey, UsdVolumeBucket)> for CandidDailyUsdVolume {
+    fn from(
+        (BridgeStatsKey(day, operator, token), bucket): (BridgeStatsKey, UsdVolumeBucket),
+    ) -> Self {
+        Self {
+            day_start: day * NANOS_PER_DAY,
+            chain_id: token.chain_id().into(),
+            operator,
+            erc20_contract_address: token.erc20_address().to_string(),
+            deposits: bucket.deposits.into(),
+            withdrawals: bucket.withdrawals.into(),
+        }
+    }
+}
+
+// Day of a timestamp in nanoseconds, as used in bucket keys
+pub fn day_of(timestamp: u64) -> u64 {
+    timestamp / NANOS_PER_DAY
+}
+
+impl StatsWindow {
+    // First day included in the window ending today, None covers every bucket
+    pub fn first_day(&self, now: u64) -> Option<u64> {
+        let days = match self {
+            StatsWindow::LastDay => 1,
+            StatsWindow::LastWeek => 7,
+            StatsWindow::LastMonth => 30,
+            StatsWindow::AllTime => return None,
+        };
+        Some((now / NANOS_PER_DAY + 1).saturating_sub(days))
+    }
+}
+
+// Merges the daily buckets of every operator and token
+pub fn aggregate_bridge_stats(
+    buckets: impl Iterator<Item = (BridgeStatsKey, BridgeStatsBucket)>,
+) -> Vec<CandidBridgeStats> {
+    let mut aggregated: BTreeMap<(Operator, Erc20Identifier), BridgeStatsBucket> = BTreeMap::new();
+    for (BridgeStatsKey(_day, operator, token), bucket) in buckets {
+        aggregated
+            .entry((operator, token))
+            .or_default()
+            .merge(bucket);
+    }
+
+    aggregated
+        .into_iter()
+        .map(|((operator, token), bucket)| CandidBridgeStats {
+            chain_id: token.chain_id().into(),
+            operator,
+            erc20_contract_address: token.erc20_address().to_string(),
+            deposits: bucket.deposits.into(),
+            withdrawals: bucket.withdrawals.into(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Nat;
+
+    #[test]
+    fn should_compute_latency_percentiles_with_nearest_rank() {
+        let histogram = |latencies: &[u64]| {
+            let mut histogram = LatencyHistogram::default();
+            for latency in latencies {
+                histogram.record(latency * NANOS_PER_SECOND);
+            }
+            histogram
+        };
+        let percentile_seconds = |histogram: &LatencyHistogram, percentile: u64| {
+            histogram
+                .percentile(percentile)
+                .map(|latency| latency / NANOS_PER_SECOND)
+        };
+
+        let mut latencies = histogram(&(1..=20).collect::<Vec<u64>>());
+        assert_eq!(percentile_seconds(&latencies, 50), Some(10));
+        assert_eq!(percentile_seconds(&latencies, 95), Some(20));
+        assert_eq!(percentile_seconds(&histogram(&[7]), 95), Some(7));
+        assert_eq!(percentile_seconds(&histogram(&[]), 50), None);
+
+        // Merged days keep the same size and latencies beyond the last bound stay reachable
+        let slow = histogram(&[200_000; 20]);
+        latencies.merge(&slow);
+        assert_eq!(latencies.counts.len(), slow.counts.len());
+        assert_eq!(percentile_seconds(&latencies, 50), Some(20));
+        assert_eq!(percentile_seconds(&latencies, 95), Some(200_000));
+    }
+
+    #[test]
+    fn should_value_transfers_in_whole_tokens() {
+        let price = |usd_price: &str| UsdPrice::from_decimal_str(usd_price, USD_DECIMALS).unwrap();
+        let amount = Erc20TokenAmount::new(2_500_000_000_000_000_000);
+
+        let value = TransferUsdValue::new(amount, 18, price("1800.5")).unwrap();
+        assert_eq!(value.usd_value.to_decimal_string(USD_DECIMALS), "4501.25");
+        // Both usd amounts are published as fixed point, never as floats
+        assert_eq!(
+            CandidTransferUsdValue::from(value),
+            CandidTransferUsdValue {
+                usd_price: "1800.5".to_string(),
+                usd_value: "4501.25".to_string(),
+                usd_price_e18: Nat::from(1_800_500_000_000_000_000_000_u128),
+                usd_value_e18: Nat::from(4_501_250_000_000_000_000_000_u128),
+            }
+        );
+        assert_eq!(
+            TransferUsdValue::new(Erc20TokenAmount::new(1_500_000), 6, price("1"))
+                .map(|value| value.usd_value.to_decimal_string(USD_DECIMALS)),
+            Some("1.5".to_string())
+        );
+        assert_eq!(TransferUsdValue::new(amount, 18, UsdPrice::ZERO), None);
+    }
+
+    #[test]
+    fn should_include_only_days_of_the_window() {
+        let now = 10 * NANOS_PER_DAY + 5;
+        assert_eq!(StatsWindow::LastDay.first_day(now), Some(10));
+        assert_eq!(StatsWindow::LastWeek.first_day(now), Some(4));
+        assert_eq!(StatsWindow::LastMonth.first_day(now), Some(0));
+        assert_eq!(StatsWindow::AllTime.first_day(now), None);
+    }
+}
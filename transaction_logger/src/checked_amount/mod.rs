@@ -148,6 +148,77 @@ impl<Unit> CheckedAmountOf<Unit> {
         self.0.as_f64()
     }
 
+    /// Parses a non-negative decimal number into an amount with `decimals` fractional digits.
+    /// Fractional digits beyond `decimals` are truncated.
+    /// ```
+    /// use evm_minter::checked_amount::CheckedAmountOf;
+    ///
+    /// enum MetricApple{}
+    /// type Apples = CheckedAmountOf<MetricApple>;
+    ///
+    /// assert_eq!(Apples::from_decimal_str("12.345", 2), Ok(Apples::from(1_234_u32)));
+    /// assert_eq!(Apples::from_decimal_str("0.5", 3), Ok(Apples::from(500_u32)));
+    /// assert!(Apples::from_decimal_str("-1", 2).is_err());
+    /// ```
+    pub fn from_decimal_str(src: &str, decimals: u8) -> Result<Self, String> {
+        let (integer, fraction) = src.split_once('.').unwrap_or((src, ""));
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+        {
+            return Err(format!("invalid decimal number: {src}"));
+        }
+
+        let mut digits: String = integer.to_string();
+        digits.extend(
+            fraction
+                .chars()
+                .chain(std::iter::repeat('0'))
+                .take(decimals as usize),
+        );
+        if digits.is_empty() {
+            return Ok(Self::ZERO);
+        }
+        ethnum::u256::from_str_radix(&digits, 10)
+            .map(Self::from_inner)
+            .map_err(|e| format!("decimal number {src} does not fit in a U256: {e}"))
+    }
+
+    /// Converts a float through its shortest decimal representation, `None` if it is negative
+    /// or not finite.
+    pub fn from_decimal_f64(value: f64, decimals: u8) -> Option<Self> {
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+        Self::from_decimal_str(&value.to_string(), decimals).ok()
+    }
+
+    /// Formats the amount as a decimal number with `decimals` fractional digits, without
+    /// trailing zeros.
+    /// ```
+    /// use evm_minter::checked_amount::CheckedAmountOf;
+    ///
+    /// enum MetricApple{}
+    /// type Apples = CheckedAmountOf<MetricApple>;
+    ///
+    /// assert_eq!(Apples::from(1_234_u32).to_decimal_string(2), "12.34".to_string());
+    /// assert_eq!(Apples::from(500_u32).to_decimal_string(3), "0.5".to_string());
+    /// assert_eq!(Apples::from(7_u32).to_decimal_string(0), "7".to_string());
+    /// ```
+    pub fn to_decimal_string(&self, decimals: u8) -> String {
+        let (integer, fraction) = match ethnum::u256::from(10_u8).checked_pow(decimals as u32) {
+            Some(unit) => (self.0 / unit, self.0 % unit),
+            None => (ethnum::u256::ZERO, self.0),
+        };
+        if fraction == ethnum::u256::ZERO {
+            return integer.to_string();
+        }
+        let fraction = fraction.to_string();
+        let padding = "0".repeat((decimals as usize).saturating_sub(fraction.len()));
+        format!("{integer}.{padding}{}", fraction.trim_end_matches('0'))
+    }
+
     /// Returns the display implementation of the inner value.
     /// Useful to avoid thousands separators if value is used for example in URLs.
     /// ```
@@ -35,5 +35,39 @@ mod checked_div_ceil {
     }
 }
 
+mod decimal_string {
+    use super::Amount;
+    use proptest::prelude::any;
+    use proptest::proptest;
+
+    proptest! {
+        #[test]
+        fn should_parse_formatted_amount(amount in any::<u128>(), decimals in 0_u8..=30) {
+            let amount = Amount::from(amount);
+            assert_eq!(
+                Amount::from_decimal_str(&amount.to_decimal_string(decimals), decimals),
+                Ok(amount)
+            );
+        }
+    }
+
+    #[test]
+    fn should_truncate_extra_fractional_digits() {
+        assert_eq!(
+            Amount::from_decimal_str("1.239", 2),
+            Ok(Amount::from(123_u8))
+        );
+        assert_eq!(Amount::from_decimal_str(".5", 1), Ok(Amount::from(5_u8)));
+        assert_eq!(Amount::from_decimal_str("3.", 0), Ok(Amount::from(3_u8)));
+    }
+
+    #[test]
+    fn should_reject_invalid_decimal_numbers() {
+        for invalid in ["", ".", "1e-7", "1.2.3", "NaN", "+1", " 1"] {
+            assert!(Amount::from_decimal_str(invalid, 18).is_err(), "{invalid}");
+        }
+    }
+}
+
 enum Unit {}
 type Amount = CheckedAmountOf<Unit>;
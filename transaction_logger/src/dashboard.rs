@@ -0,0 +1,133 @@
+// Html summary of the scraper state served under /dashboard
+
+use std::fmt::Write;
+
+use crate::endpoints::{CandidEvmToIcp, CandidIcpToEvm, Transaction};
+use crate::state::{Minter, MinterKey};
+
+// Number of transactions listed on the dashboard
+pub const DASHBOARD_RECENT_TRANSACTIONS: usize = 50;
+
+pub struct DashboardData {
+    pub minters: Vec<(MinterKey, Minter)>,
+    pub evm_to_icp_count: u64,
+    pub icp_to_evm_count: u64,
+    pub recent_transactions: Vec<Transaction>,
+}
+
+impl DashboardData {
+    pub fn render(&self) -> String {
+        let mut html = String::new();
+
+        html.push_str(
+            "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\">\
+             <title>Appic Transaction Logger</title>\
+             <style>\
+             body { font-family: monospace; }\
+             table { border-collapse: collapse; margin-bottom: 24px; }\
+             th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }\
+             </style></head><body>",
+        );
+
+        html.push_str("<h1>Appic Transaction Logger</h1>");
+        let _ = write!(
+            html,
+            "<p>Evm to Icp transactions: {}<br>Icp to Evm transactions: {}</p>",
+            self.evm_to_icp_count, self.icp_to_evm_count
+        );
+
+        self.render_minters(&mut html);
+        self.render_recent_transactions(&mut html);
+
+        html.push_str("</body></html>");
+        html
+    }
+
+    fn render_minters(&self, html: &mut String) {
+        html.push_str(
+            "<h2>Minters</h2><table><thead><tr>\
+             <th>Chain Id</th><th>Operator</th><th>Minter Id</th>\
+             <th>Last Observed Event</th><th>Last Scraped Event</th><th>Lag</th>\
+             <th>Evm to Icp Fee</th><th>Icp to Evm Fee</th>\
+             </tr></thead><tbody>",
+        );
+
+        for (key, minter) in &self.minters {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td><td>{}</td></tr>",
+                key.chain_id().0,
+                key.operator(),
+                minter.id,
+                minter.last_observed_event,
+                minter.last_scraped_event,
+                minter
+                    .last_observed_event
+                    .saturating_sub(minter.last_scraped_event),
+                minter.evm_to_icp_fee,
+                minter.icp_to_evm_fee,
+            );
+        }
+
+        html.push_str("</tbody></table>");
+    }
+
+    fn render_recent_transactions(&self, html: &mut String) {
+        html.push_str(
+            "<h2>Recent Transactions</h2><table><thead><tr>\
+             <th>Time</th><th>Direction</th><th>Chain Id</th><th>Operator</th>\
+             <th>Id</th><th>Token</th><th>Amount</th><th>Status</th>\
+             </tr></thead><tbody>",
+        );
+
+        for tx in &self.recent_transactions {
+            match tx {
+                Transaction::EvmToIcp(tx) => render_evm_to_icp(html, tx),
+                Transaction::IcpToEvm(tx) => render_icp_to_evm(html, tx),
+            }
+        }
+
+        html.push_str("</tbody></table>");
+    }
+}
+
+fn render_evm_to_icp(html: &mut String, tx: &CandidEvmToIcp) {
+    let _ = write!(
+        html,
+        "<tr><td>{}</td><td>Evm to Icp</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td>\
+         <td>{}</td><td>{}</td></tr>",
+        tx.time,
+        tx.chain_id.0,
+        tx.operator,
+        escape_html(&tx.transaction_hash),
+        escape_html(&tx.erc20_contract_address),
+        tx.value.0,
+        escape_html(&format!("{:?}", tx.status)),
+    );
+}
+
+fn render_icp_to_evm(html: &mut String, tx: &CandidIcpToEvm) {
+    let _ = write!(
+        html,
+        "<tr><td>{}</td><td>Icp to Evm</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td>\
+         <td>{}</td><td>{:?}</td></tr>",
+        tx.time,
+        tx.chain_id.0,
+        tx.operator,
+        tx.native_ledger_burn_index.0,
+        escape_html(&tx.erc20_contract_address),
+        tx.withdrawal_amount.0,
+        tx.status,
+    );
+}
+
+// Values coming from minter events are not trusted to be valid html
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
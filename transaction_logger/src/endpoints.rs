@@ -1,10 +1,12 @@
 use std::str::FromStr;
 
+use crate::numeric::{BlockNumber, UsdPrice, USD_DECIMALS};
 use crate::state::{
     checked_nat_to_erc20_amount, nat_to_u128, read_state, ChainId, Erc20Identifier,
     Erc20TwinLedgerSuiteFee, Erc20TwinLedgerSuiteRequest, Erc20TwinLedgerSuiteStatus,
-    EvmToIcpStatus, EvmToIcpTx, EvmToken, IcpToEvmStatus, IcpToEvmTx, IcpToken, IcpTokenType,
-    Operator,
+    EvmToIcpStatus, EvmToIcpTx, EvmToken, FailedScrapeRange, FailedScrapeRangeKey, IcpToEvmStatus,
+    IcpToEvmTx, IcpToken, IcpTokenType, Minter, MinterKey, Operator, Reimbursement, ScrapeHealth,
+    SyncedBlocks, TimelineEntry, TimelineStatus, TransactionAttempt,
 };
 use candid::{CandidType, Deserialize, Nat, Principal};
 use ic_ethereum_types::Address;
@@ -99,6 +101,288 @@ pub struct UpgradeArg {
     pub update_minters: Option<Vec<UpdateMinterArgs>>,
 }
 
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct MinterKeyArgs {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+}
+
+impl From<&MinterKeyArgs> for MinterKey {
+    fn from(value: &MinterKeyArgs) -> Self {
+        Self(ChainId::from(&value.chain_id), value.operator)
+    }
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ResetLastScrapedEventArgs {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+    pub last_scraped_event: Nat,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct UpdateMinterScrapeIntervalArgs {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+    pub interval_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum ManageMinterError {
+    MinterAlreadyExists,
+    MinterNotFound,
+    InvalidFee,
+    InvalidEventIndex,
+    InvalidScrapeInterval,
+    // The event index is past the latest event observed on the minter
+    EventNotObserved { last_observed_event: u64 },
+}
+
+// Range of minter events to fetch and apply again, both ends are inclusive
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RescanMinterEventsArgs {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+    pub from_event: u64,
+    pub to_event: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum RescanMinterEventsError {
+    MinterNotFound,
+    InvalidRange,
+    AlreadyRescanning,
+    // Events before next_event were reapplied
+    ScrapeFailed { next_event: u64, reason: String },
+}
+
+// Range of events skipped by the scraper that is waiting for a retry
+// Times are in nanoseconds since the unix epoch
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidFailedScrapeRange {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+    pub from_event: u64,
+    pub to_event: u64,
+    pub attempts: u32,
+    pub last_error: String,
+    pub last_attempt_time: u64,
+    pub next_retry_time: u64,
+}
+
+// Status a transaction moved to, together with the minter event that moved it
+// timestamp is the time of the event on the minter in nanoseconds since the unix epoch
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidTimelineEntry {
+    pub status: TimelineStatus,
+    pub event_index: u64,
+    pub timestamp: u64,
+}
+
+impl From<(u64, TimelineEntry)> for CandidTimelineEntry {
+    fn from((event_index, entry): (u64, TimelineEntry)) -> Self {
+        Self {
+            status: entry.status,
+            event_index,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+// A transaction sent for a withdrawal and the minter event that created it
+// transaction_hash is set once the transaction is signed
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidTransactionAttempt {
+    pub nonce: u64,
+    pub max_fee_per_gas: Nat,
+    pub max_priority_fee_per_gas: Nat,
+    pub gas_limit: Nat,
+    pub transaction_hash: Option<String>,
+    pub event_index: u64,
+    pub timestamp: u64,
+}
+
+impl From<(u64, TransactionAttempt)> for CandidTransactionAttempt {
+    fn from((event_index, attempt): (u64, TransactionAttempt)) -> Self {
+        Self {
+            nonce: attempt.nonce,
+            max_fee_per_gas: attempt.max_fee_per_gas.into(),
+            max_priority_fee_per_gas: attempt.max_priority_fee_per_gas.into(),
+            gas_limit: attempt.gas_limit.into(),
+            transaction_hash: attempt.transaction_hash,
+            event_index,
+            timestamp: attempt.timestamp,
+        }
+    }
+}
+
+impl From<(FailedScrapeRangeKey, FailedScrapeRange)> for CandidFailedScrapeRange {
+    fn from((key, range): (FailedScrapeRangeKey, FailedScrapeRange)) -> Self {
+        let FailedScrapeRangeKey(minter_key, from_event) = key;
+        Self {
+            chain_id: minter_key.chain_id().into(),
+            operator: minter_key.operator(),
+            from_event,
+            to_event: range.to_event,
+            attempts: range.attempts,
+            last_error: range.last_error,
+            last_attempt_time: range.last_attempt_time,
+            next_retry_time: range.next_retry_time,
+        }
+    }
+}
+
+// Scraping progress and health of a single minter
+// event_lag is the number of observed events that are not scraped yet
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct MinterStatus {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+    pub minter_id: Principal,
+    pub enabled: bool,
+    pub last_observed_event: u64,
+    pub last_scraped_event: u64,
+    pub event_lag: u64,
+    pub evm_to_icp_fee: Nat,
+    pub icp_to_evm_fee: Nat,
+    pub last_success_time: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_error_time: Option<u64>,
+    pub consecutive_failures: u64,
+    pub scrape_interval_seconds: u64,
+}
+
+// Estimated mint time of a deposit that is not minted yet, in nanoseconds since the unix epoch
+// None when the minter has not minted any deposit with a known latency yet
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidDepositEta {
+    pub status: EvmToIcpStatus,
+    pub block_number: Option<Nat>,
+    pub minter_synced_block: Option<Nat>,
+    pub latency_samples: u64,
+    pub median_latency_seconds: Option<u64>,
+    pub estimated_mint_time: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StatsWindow {
+    LastDay,
+    LastWeek,
+    LastMonth,
+    AllTime,
+}
+
+// Latencies are measured from acceptance to mint for deposits and to successful finalization for
+// withdrawals, failure rate is the share of accepted transfers that were invalidated, quarantined
+// or reimbursed
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Serialize)]
+pub struct CandidTransferStats {
+    pub count: u64,
+    pub volume: Nat,
+    pub completed: u64,
+    pub failed: u64,
+    pub failure_rate: f64,
+    pub median_latency_seconds: Option<u64>,
+    pub p95_latency_seconds: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Serialize)]
+pub struct CandidBridgeStats {
+    pub chain_id: Nat,
+    pub operator: Operator,
+    pub erc20_contract_address: String,
+    pub deposits: CandidTransferStats,
+    pub withdrawals: CandidTransferStats,
+}
+
+// Usd value of a transfer when the minter accepted it, valued with the price of its icp twin token
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CandidTransferUsdValue {
+    pub usd_price: String,
+    pub usd_value: String,
+    pub usd_price_e18: Nat,
+    pub usd_value_e18: Nat,
+}
+
+// Time range in nanoseconds since the unix epoch, both ends included
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GetUsdVolumeArgs {
+    pub from: u64,
+    pub to: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CandidUsdVolume {
+    pub transfers: u64,
+    pub usd_value: String,
+    pub usd_value_e18: Nat,
+}
+
+// Usd volume of the transfers of a bridge pair accepted on the day starting at day_start
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Serialize)]
+pub struct CandidDailyUsdVolume {
+    pub day_start: u64,
+    pub chain_id: Nat,
+    pub operator: Operator,
+    pub erc20_contract_address: String,
+    pub deposits: CandidUsdVolume,
+    pub withdrawals: CandidUsdVolume,
+}
+
+// Evm blocks a minter has scraped, deposits in later blocks are still expected to be picked up
+// Erc20 and deposit with subaccount blocks are only reported by dfinity minters
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidMinterSyncStatus {
+    pub chain_id: CandidChainId,
+    pub operator: Operator,
+    pub last_synced_block: Option<Nat>,
+    pub last_synced_erc20_block: Option<Nat>,
+    pub last_synced_deposit_with_subaccount_block: Option<Nat>,
+    pub skipped_blocks: Vec<Nat>,
+}
+
+impl From<(MinterKey, SyncedBlocks, Vec<BlockNumber>)> for CandidMinterSyncStatus {
+    fn from(
+        (minter_key, synced_blocks, skipped_blocks): (MinterKey, SyncedBlocks, Vec<BlockNumber>),
+    ) -> Self {
+        Self {
+            chain_id: minter_key.chain_id().into(),
+            operator: minter_key.operator(),
+            last_synced_block: synced_blocks.native.map(Nat::from),
+            last_synced_erc20_block: synced_blocks.erc20.map(Nat::from),
+            last_synced_deposit_with_subaccount_block: synced_blocks
+                .deposit_with_subaccount
+                .map(Nat::from),
+            skipped_blocks: skipped_blocks.into_iter().map(Nat::from).collect(),
+        }
+    }
+}
+
+impl From<(Minter, ScrapeHealth, bool, u64)> for MinterStatus {
+    fn from(
+        (minter, health, enabled, scrape_interval_seconds): (Minter, ScrapeHealth, bool, u64),
+    ) -> Self {
+        Self {
+            chain_id: minter.chain_id.into(),
+            operator: minter.operator,
+            minter_id: minter.id,
+            enabled,
+            last_observed_event: minter.last_observed_event,
+            last_scraped_event: minter.last_scraped_event,
+            event_lag: minter
+                .last_observed_event
+                .saturating_sub(minter.last_scraped_event),
+            evm_to_icp_fee: minter.evm_to_icp_fee.into(),
+            icp_to_evm_fee: minter.icp_to_evm_fee.into(),
+            last_success_time: health.last_success_time,
+            last_error: health.last_error,
+            last_error_time: health.last_error_time,
+            consecutive_failures: health.consecutive_failures,
+            scrape_interval_seconds,
+        }
+    }
+}
+
 #[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum LoggerArgs {
     Init(InitArgs),
@@ -125,6 +409,35 @@ pub enum TransactionSearchParam {
     TxMintId(Nat),
 }
 
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct GetTxsByAddressArgs {
+    pub address: String,
+    pub limit: u64,
+    pub cursor: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct GetTxsByPrincipalArgs {
+    pub principal_id: Principal,
+    pub limit: u64,
+    pub cursor: Option<String>,
+}
+
+// A single page of transactions, newest first
+// next_cursor is none once the last page is reached, total_count is only set on the first page
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct TransactionsPage {
+    pub transactions: Vec<Transaction>,
+    pub next_cursor: Option<String>,
+    pub total_count: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum GetTxsError {
+    InvalidAddress(String),
+    InvalidCursor(String),
+}
+
 impl From<CandidIcpToEvm> for Transaction {
     fn from(value: CandidIcpToEvm) -> Self {
         Self::IcpToEvm(value)
@@ -158,10 +471,31 @@ pub struct CandidIcpToEvm {
     pub status: IcpToEvmStatus,
     pub operator: Operator,
     pub chain_id: Nat,
+    pub reimbursement: Option<CandidReimbursement>,
 }
 
-impl From<IcpToEvmTx> for CandidIcpToEvm {
-    fn from(value: IcpToEvmTx) -> Self {
+// reimbursed_in_block is the index of the reimbursement block on the ledger
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidReimbursement {
+    pub reimbursed_amount: Nat,
+    pub ledger_id: Option<Principal>,
+    pub reimbursed_in_block: Option<Nat>,
+    pub transaction_hash: Option<String>,
+}
+
+impl From<Reimbursement> for CandidReimbursement {
+    fn from(value: Reimbursement) -> Self {
+        Self {
+            reimbursed_amount: value.reimbursed_amount.into(),
+            ledger_id: value.ledger_id,
+            reimbursed_in_block: value.reimbursed_in_block.map(|block| block.get().into()),
+            transaction_hash: value.transaction_hash,
+        }
+    }
+}
+
+impl From<(IcpToEvmTx, Option<Reimbursement>)> for CandidIcpToEvm {
+    fn from((value, reimbursement): (IcpToEvmTx, Option<Reimbursement>)) -> Self {
         let IcpToEvmTx {
             transaction_hash,
             native_ledger_burn_index,
@@ -206,6 +540,7 @@ impl From<IcpToEvmTx> for CandidIcpToEvm {
             status,
             operator,
             chain_id: Nat::from(chain_id),
+            reimbursement: reimbursement.map(CandidReimbursement::from),
         }
     }
 }
@@ -286,10 +621,14 @@ pub struct CandidEvmToken {
     pub decimals: u8,
     pub symbol: String,
     pub logo: String,
+    // "0" if the token has no price
+    pub usd_price: String,
+    // Usd price in fixed point with 18 decimals, None if the token has no price
+    pub usd_price_e18: Option<Nat>,
 }
 
-impl From<EvmToken> for CandidEvmToken {
-    fn from(value: EvmToken) -> Self {
+impl From<(EvmToken, Option<UsdPrice>)> for CandidEvmToken {
+    fn from((value, usd_price): (EvmToken, Option<UsdPrice>)) -> Self {
         Self {
             chain_id: value.chain_id.into(),
             erc20_contract_address: value.erc20_contract_address.to_string(),
@@ -297,15 +636,98 @@ impl From<EvmToken> for CandidEvmToken {
             decimals: value.decimals,
             symbol: value.symbol,
             logo: value.logo,
+            usd_price: usd_price
+                .unwrap_or(UsdPrice::ZERO)
+                .to_decimal_string(USD_DECIMALS),
+            usd_price_e18: usd_price.map(Nat::from),
         }
     }
 }
 
+impl From<EvmToken> for CandidEvmToken {
+    fn from(value: EvmToken) -> Self {
+        Self::from((value, None))
+    }
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct EvmTokenArgs {
+    pub chain_id: CandidChainId,
+    pub erc20_contract_address: String,
+    pub name: String,
+    pub decimals: u8,
+    pub symbol: String,
+    pub logo: String,
+}
+
+impl TryFrom<EvmTokenArgs> for EvmToken {
+    type Error = ManageEvmTokenError;
+
+    fn try_from(value: EvmTokenArgs) -> Result<Self, Self::Error> {
+        let erc20_contract_address = Address::from_str(&value.erc20_contract_address)
+            .map_err(|_| ManageEvmTokenError::InvalidAddress(value.erc20_contract_address))?;
+        Ok(Self {
+            chain_id: ChainId::from(&value.chain_id),
+            erc20_contract_address,
+            name: value.name,
+            decimals: value.decimals,
+            symbol: value.symbol,
+            logo: value.logo,
+        })
+    }
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct SetEvmTokenHiddenArgs {
+    pub address: String,
+    pub chain_id: CandidChainId,
+    pub hidden: bool,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum ManageEvmTokenError {
+    InvalidAddress(String),
+    TokenAlreadyExists,
+    TokenNotFound,
+    InvalidJson(String),
+}
+
+// A usd price of None removes the pushed price
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct UpdateEvmTokenPriceArgs {
+    pub address: String,
+    pub chain_id: CandidChainId,
+    pub usd_price_e18: Option<Nat>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum UpdateEvmTokenPriceError {
+    InvalidAddress(String),
+    InvalidUsdPrice(String),
+    TokenNotFound(String),
+}
+
 #[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct GetIcpTokenArgs {
     pub ledger_id: Principal,
 }
 
+// Time range in nanoseconds since the unix epoch, both ends included
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct GetTokenPriceHistoryArgs {
+    pub ledger_id: Principal,
+    pub from: u64,
+    pub to: u64,
+}
+
+// Usd price at the start of the hour or day of timestamp
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CandidPricePoint {
+    pub timestamp: u64,
+    pub usd_price: String,
+    pub usd_price_e18: Nat,
+}
+
 #[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct CandidIcpToken {
     pub ledger_id: Principal,
@@ -317,6 +739,8 @@ pub struct CandidIcpToken {
     pub usd_price: String,
     pub fee: Nat,
     pub rank: Option<u32>,
+    // Usd price in fixed point with 18 decimals, takes precedence over usd_price when adding tokens
+    pub usd_price_e18: Option<Nat>,
 }
 
 impl From<IcpToken> for CandidIcpToken {
@@ -327,7 +751,8 @@ impl From<IcpToken> for CandidIcpToken {
             decimals: value.decimals,
             symbol: value.symbol,
             logo: value.logo,
-            usd_price: value.usd_price,
+            usd_price: value.usd_price.to_decimal_string(USD_DECIMALS),
+            usd_price_e18: Some(Nat::from(value.usd_price)),
             token_type: value.token_type,
             fee: value.fee.into(),
             rank: value.rank,
@@ -343,7 +768,11 @@ impl From<CandidIcpToken> for IcpToken {
             decimals: value.decimals,
             symbol: value.symbol,
             logo: value.logo,
-            usd_price: value.usd_price,
+            usd_price: value
+                .usd_price_e18
+                .and_then(|usd_price| UsdPrice::try_from(usd_price).ok())
+                .or_else(|| UsdPrice::from_decimal_str(&value.usd_price, USD_DECIMALS).ok())
+                .unwrap_or(UsdPrice::ZERO),
             token_type: value.token_type,
             fee: checked_nat_to_erc20_amount(value.fee).unwrap(),
             rank: value.rank,
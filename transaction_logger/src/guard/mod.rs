@@ -4,14 +4,19 @@ use serde::{Deserialize, Serialize};
 
 use std::collections::HashSet;
 
-#[derive(Clone, PartialEq, Hash, Debug, PartialOrd, Eq, Ord, Deserialize, Serialize, Copy)]
+use crate::state::MinterKey;
+
+#[derive(Clone, PartialEq, Hash, Debug, PartialOrd, Eq, Ord, Deserialize, Serialize)]
 pub enum TaskType {
     RemoveUnverified,
     ScrapeEvents,
+    ScrapeMinter(MinterKey),
     UpdateBridgePairs,
     UpdateIcpTokens,
     RemoveInvalidTokens,
     UpdateUsdPrice,
+    RescanEvents,
+    RetryFailedScrapeRanges,
 }
 
 thread_local! {
@@ -44,12 +49,17 @@ pub enum TimerGuardError {
 impl TimerGuard {
     pub fn new(task: TaskType) -> Result<Self, TimerGuardError> {
         mutate_active_tasks(|active_tasks| {
-            if !active_tasks.insert(task) {
+            if !active_tasks.insert(task.clone()) {
                 return Err(TimerGuardError::AlreadyProcessing);
             }
             Ok(Self { task })
         })
     }
+
+    // Checks whether a guard of the task is held without taking one
+    pub fn is_active(task: &TaskType) -> bool {
+        mutate_active_tasks(|active_tasks| active_tasks.contains(task))
+    }
 }
 
 impl Drop for TimerGuard {
@@ -7,7 +7,8 @@ use candid::{self, CandidType, Decode, Deserialize, Encode, Principal};
 use ic_cdk::api::call::CallResult as Result;
 
 use crate::{
-    numeric::Erc20TokenAmount,
+    icp_tokens_service::ICP_TOKEN_LOGO_BASE_URL,
+    numeric::{Erc20TokenAmount, UsdPrice},
     state::{
         checked_nat_to_erc20_amount, checked_nat_to_u64, checked_nat_to_u8, nat_to_erc20_amount,
         nat_to_u64, nat_to_u8, IcpToken, IcpTokenType,
@@ -63,11 +64,8 @@ impl From<TokenMetadata> for IcpToken {
             decimals: checked_nat_to_u8(&value.decimals).unwrap_or(0),
             symbol: value.symbol,
             token_type,
-            logo: format!(
-                "https://wqfao-piaaa-aaaag-qj5ba-cai.raw.icp0.io/{}",
-                ledger_id
-            ),
-            usd_price: "0".to_string(),
+            logo: format!("{}{}", ICP_TOKEN_LOGO_BASE_URL, ledger_id),
+            usd_price: UsdPrice::ZERO,
             fee: checked_nat_to_erc20_amount(value.fee).unwrap_or(Erc20TokenAmount::ZERO),
             rank: Some(value.rank),
         }
@@ -0,0 +1,27 @@
+// Subset of the kong swap backend interface used for pricing tokens
+// Fields of PoolReply that are not needed are left out, candid ignores them when decoding
+use candid::{self, CandidType, Deserialize};
+
+#[derive(CandidType, Deserialize, Debug)]
+pub struct PoolReply {
+    pub pool_id: u32,
+    pub name: String,
+    pub symbol: String,
+    pub chain_0: String,
+    pub symbol_0: String,
+    pub address_0: String,
+    pub balance_0: candid::Nat,
+    pub chain_1: String,
+    pub symbol_1: String,
+    pub address_1: String,
+    pub balance_1: candid::Nat,
+    // Amount of token 1 paid for one token 0
+    pub price: f64,
+    pub is_removed: bool,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub enum PoolsResult {
+    Ok(Vec<PoolReply>),
+    Err(String),
+}
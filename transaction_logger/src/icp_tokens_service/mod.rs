@@ -1,35 +1,78 @@
 // Token service updates list of available tokens on icp on a daily basis
 // Tokens are fetched from icpswap token list canister and sonic swap
+// Usd prices are quoted by icpswap, sonic swap, kong swap and the exchange rate canister
+
+use std::collections::HashMap;
+use std::str::FromStr;
 
 use candid::Principal;
 use ic_canister_log::log;
 use icp_swap_token_type::TokensListResult;
 use icp_swap_usd_node_types::PublicTokenOverview;
-use sonic_swap_types::TokenInfoWithType;
+use kong_swap_types::PoolsResult;
+use num_traits::ToPrimitive;
+use price_oracle::{pool_usd_price, scaled_usd_price, PriceQuote, PriceSource};
+use sonic_swap_types::{PairInfoExt, TokenInfoWithType};
+use xrc_types::{Asset, AssetClass, GetExchangeRateRequest, GetExchangeRateResult};
 
 use crate::{
     logs::INFO,
     minter_clinet::{CallError, IcRunTime, Reason, Runtime},
-    numeric::Erc20TokenAmount,
+    numeric::{Erc20TokenAmount, UsdPrice, USD_DECIMALS},
     state::{IcpToken, IcpTokenType},
 };
 
-mod icp_swap_token_type;
-mod icp_swap_usd_node_types;
-mod sonic_swap_types;
+pub(crate) mod icp_swap_token_type;
+pub(crate) mod icp_swap_usd_node_types;
+pub(crate) mod kong_swap_types;
+pub mod price_oracle;
+pub(crate) mod sonic_swap_types;
+pub(crate) mod xrc_types;
+
+pub(crate) const SONIC_ID: &str = "3xwpq-ziaaa-aaaah-qcn4a-cai";
+pub(crate) const ICP_SWAP_ID: &str = "k37c6-riaaa-aaaag-qcyza-cai";
+pub(crate) const ICP_SWAP_NODE: &str = "ggzvv-5qaaa-aaaag-qck7a-cai";
+pub(crate) const KONG_SWAP_ID: &str = "2ipq2-uqaaa-aaaar-qailq-cai";
+pub(crate) const XRC_ID: &str = "uf6dk-hyaaa-aaaaq-qaaaq-cai";
+
+// Logos of icp tokens are served under their ledger id
+pub const ICP_TOKEN_LOGO_BASE_URL: &str = "https://wqfao-piaaa-aaaag-qj5ba-cai.raw.icp0.io/";
+
+// Cycles attached to every exchange rate canister call
+const XRC_CALL_CYCLES: u128 = 1_000_000_000;
+
+// Usd stablecoins and their decimals, pools against them price the other token
+pub(crate) const USD_STABLECOINS: [(&str, u8); 2] = [
+    ("xevnm-gaaaa-aaaar-qafnq-cai", 6), // ckUSDC
+    ("cngnf-vqaaa-aaaar-qag4q-cai", 6), // ckUSDT
+];
+
+// Ledgers priced by the exchange rate canister and the symbol of their underlying asset
+pub(crate) const XRC_ASSETS: [(&str, &str); 3] = [
+    ("ryjl3-tyaaa-aaaaa-aaaba-cai", "ICP"),
+    ("mxzaz-hqaaa-aaaar-qaada-cai", "BTC"), // ckBTC
+    ("ss2fx-dyaaa-aaaar-qacoq-cai", "ETH"), // ckETH
+];
 
-const SONIC_ID: &str = "3xwpq-ziaaa-aaaah-qcn4a-cai";
-const ICP_SWAP_ID: &str = "k37c6-riaaa-aaaag-qcyza-cai";
-const ICP_SWAP_NODE: &str = "ggzvv-5qaaa-aaaag-qck7a-cai";
-pub struct TokenService {
-    runtime: IcRunTime,
+fn stablecoin_decimals(ledger_id: &str) -> Option<u8> {
+    USD_STABLECOINS
+        .iter()
+        .find(|(stablecoin, _)| *stablecoin == ledger_id)
+        .map(|(_, decimals)| *decimals)
+}
+pub struct TokenService<R: Runtime = IcRunTime> {
+    runtime: R,
 }
 
 impl TokenService {
     pub fn new() -> Self {
-        Self {
-            runtime: IcRunTime {},
-        }
+        Self::with_runtime(IcRunTime {})
+    }
+}
+
+impl<R: Runtime> TokenService<R> {
+    pub fn with_runtime(runtime: R) -> Self {
+        Self { runtime }
     }
 
     pub async fn get_sonic_tokens(&self) -> Vec<IcpToken> {
@@ -163,4 +206,192 @@ impl TokenService {
             )
             .await
     }
+
+    // Usd price quotes of a single source, tokens are needed for their decimals
+    pub async fn get_usd_price_quotes(
+        &self,
+        source: PriceSource,
+        tokens: &[IcpToken],
+        now: u64,
+    ) -> Result<Vec<PriceQuote>, CallError> {
+        match source {
+            PriceSource::IcpSwap => self.get_icp_swap_quotes(now).await,
+            PriceSource::Sonic => self.get_sonic_quotes(tokens).await,
+            PriceSource::KongSwap => self.get_kong_swap_quotes(now).await,
+            PriceSource::ExchangeRateCanister => self.get_xrc_quotes().await,
+        }
+    }
+
+    async fn get_icp_swap_quotes(&self, now: u64) -> Result<Vec<PriceQuote>, CallError> {
+        let tokens = self.get_icp_swap_tokens_with_usd_price().await?;
+
+        Ok(tokens
+            .into_iter()
+            .filter(|token| token.priceUSD != 0_f64 && token.volumeUSD7d != 0_f64)
+            .filter_map(|token| {
+                Some(PriceQuote {
+                    source: PriceSource::IcpSwap,
+                    ledger_id: Principal::from_str(&token.address).ok()?,
+                    usd_price: UsdPrice::from_decimal_f64(token.priceUSD, USD_DECIMALS)?,
+                    timestamp: now,
+                })
+            })
+            .collect())
+    }
+
+    // Prices tokens from the reserves of their sonic pools against usd stablecoins, quotes are
+    // stamped with the time the reserves of the pool last changed
+    async fn get_sonic_quotes(&self, tokens: &[IcpToken]) -> Result<Vec<PriceQuote>, CallError> {
+        let pairs = self
+            .runtime
+            .call_canister::<(), Vec<PairInfoExt>>(
+                Principal::from_text(SONIC_ID).unwrap(),
+                "getAllPairs",
+                (),
+            )
+            .await?;
+
+        let decimals: HashMap<Principal, u8> = tokens
+            .iter()
+            .map(|token| (token.ledger_id, token.decimals))
+            .collect();
+
+        Ok(pairs
+            .into_iter()
+            .filter_map(|pair| {
+                let (token, token_reserve, stable_reserve, stable_decimals) = match (
+                    stablecoin_decimals(&pair.token0),
+                    stablecoin_decimals(&pair.token1),
+                ) {
+                    (None, Some(stable_decimals)) => {
+                        (pair.token0, pair.reserve0, pair.reserve1, stable_decimals)
+                    }
+                    (Some(stable_decimals), None) => {
+                        (pair.token1, pair.reserve1, pair.reserve0, stable_decimals)
+                    }
+                    _ => return None,
+                };
+                let ledger_id = Principal::from_str(&token).ok()?;
+                Some(PriceQuote {
+                    source: PriceSource::Sonic,
+                    ledger_id,
+                    usd_price: pool_usd_price(
+                        &token_reserve,
+                        *decimals.get(&ledger_id)?,
+                        &stable_reserve,
+                        stable_decimals,
+                    )?,
+                    timestamp: pair.blockTimestampLast.0.to_u64()?,
+                })
+            })
+            .collect())
+    }
+
+    // Prices tokens from the price of their kong swap pools against usd stablecoins
+    async fn get_kong_swap_quotes(&self, now: u64) -> Result<Vec<PriceQuote>, CallError> {
+        let pools = match self
+            .runtime
+            .call_canister::<Option<String>, PoolsResult>(
+                Principal::from_text(KONG_SWAP_ID).unwrap(),
+                "pools",
+                None,
+            )
+            .await?
+        {
+            PoolsResult::Ok(pools) => pools,
+            PoolsResult::Err(e) => {
+                return Err(CallError {
+                    method: "pools".to_string(),
+                    reason: Reason::Rejected(e),
+                })
+            }
+        };
+
+        Ok(pools
+            .into_iter()
+            .filter(|pool| !pool.is_removed && pool.price > 0_f64)
+            .filter_map(|pool| {
+                let (token, usd_price) = match (
+                    stablecoin_decimals(&pool.address_0),
+                    stablecoin_decimals(&pool.address_1),
+                ) {
+                    (None, Some(_)) => (pool.address_0, pool.price),
+                    (Some(_), None) => (pool.address_1, 1_f64 / pool.price),
+                    _ => return None,
+                };
+                Some(PriceQuote {
+                    source: PriceSource::KongSwap,
+                    ledger_id: Principal::from_str(&token).ok()?,
+                    usd_price: UsdPrice::from_decimal_f64(usd_price, USD_DECIMALS)?,
+                    timestamp: now,
+                })
+            })
+            .collect())
+    }
+
+    // Prices the ledgers of XRC_ASSETS, assets the exchange rate canister has no rate for or fails
+    // to price are skipped
+    async fn get_xrc_quotes(&self) -> Result<Vec<PriceQuote>, CallError> {
+        let mut quotes = Vec::with_capacity(XRC_ASSETS.len());
+
+        for (ledger_id, symbol) in XRC_ASSETS {
+            let request = GetExchangeRateRequest {
+                base_asset: Asset {
+                    symbol: symbol.to_string(),
+                    class: AssetClass::Cryptocurrency,
+                },
+                quote_asset: Asset {
+                    symbol: "USD".to_string(),
+                    class: AssetClass::FiatCurrency,
+                },
+                timestamp: None,
+            };
+            let result = match self
+                .runtime
+                .call_canister_with_cycles::<GetExchangeRateRequest, GetExchangeRateResult>(
+                    Principal::from_text(XRC_ID).unwrap(),
+                    "get_exchange_rate",
+                    request,
+                    XRC_CALL_CYCLES,
+                )
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    log!(
+                        INFO,
+                        "[Update Usd Price] Exchange rate canister failed to price {}: {:?}",
+                        symbol,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            match result {
+                GetExchangeRateResult::Ok(rate) => {
+                    let Some(usd_price) = scaled_usd_price(rate.rate, rate.metadata.decimals)
+                    else {
+                        continue;
+                    };
+                    quotes.push(PriceQuote {
+                        source: PriceSource::ExchangeRateCanister,
+                        ledger_id: Principal::from_text(ledger_id).unwrap(),
+                        usd_price,
+                        timestamp: rate.timestamp.saturating_mul(1_000_000_000),
+                    });
+                }
+                GetExchangeRateResult::Err(e) => {
+                    log!(
+                        INFO,
+                        "[Update Usd Price] Exchange rate canister has no {} rate: {:?}",
+                        symbol,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(quotes)
+    }
 }
@@ -0,0 +1,215 @@
+// Usd prices of icp tokens aggregated from several sources
+// A token's price is the median of the fresh quotes of every source, after quotes too far from
+// the median of all quotes are rejected, so a single source failing or misreporting does not
+// move or freeze prices
+
+use std::collections::BTreeMap;
+
+use candid::{Nat, Principal};
+
+use crate::numeric::{UsdPrice, USD_DECIMALS};
+
+// Sources queried on every price update
+pub const PRICE_SOURCES: [PriceSource; 4] = [
+    PriceSource::IcpSwap,
+    PriceSource::Sonic,
+    PriceSource::KongSwap,
+    PriceSource::ExchangeRateCanister,
+];
+
+// 15 Minutes, quotes older than this are ignored
+// IcpSwap and KongSwap report no time for their prices, their quotes are stamped with the time
+// of the update, so staleness rejection does not apply to them
+pub const MAX_QUOTE_AGE: u64 = 15 * 60 * 1_000_000_000;
+
+// Quotes deviating more than this from the median of all quotes of a token are ignored
+pub const MAX_QUOTE_DEVIATION_PERCENT: u64 = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum PriceSource {
+    IcpSwap,
+    Sonic,
+    KongSwap,
+    ExchangeRateCanister,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PriceQuote {
+    pub source: PriceSource,
+    pub ledger_id: Principal,
+    pub usd_price: UsdPrice,
+    // Time the source priced the token at, in nanoseconds since the unix epoch
+    pub timestamp: u64,
+}
+
+// Aggregated price of every token with at least one fresh quote left after outlier rejection
+pub fn aggregate_usd_prices(quotes: Vec<PriceQuote>, now: u64) -> BTreeMap<Principal, UsdPrice> {
+    let mut quotes_by_token: BTreeMap<Principal, Vec<UsdPrice>> = BTreeMap::new();
+    for quote in quotes {
+        if quote.usd_price == UsdPrice::ZERO || now.saturating_sub(quote.timestamp) > MAX_QUOTE_AGE
+        {
+            continue;
+        }
+        quotes_by_token
+            .entry(quote.ledger_id)
+            .or_default()
+            .push(quote.usd_price);
+    }
+
+    quotes_by_token
+        .into_iter()
+        .filter_map(|(ledger_id, prices)| {
+            aggregate_token_prices(prices).map(|usd_price| (ledger_id, usd_price))
+        })
+        .collect()
+}
+
+fn aggregate_token_prices(prices: Vec<UsdPrice>) -> Option<UsdPrice> {
+    let median = median(prices.clone())?;
+    let accepted: Vec<UsdPrice> = prices
+        .into_iter()
+        .filter(|price| is_within_deviation(*price, median))
+        .collect();
+    median(accepted)
+}
+
+fn is_within_deviation(price: UsdPrice, median: UsdPrice) -> bool {
+    let deviation = price
+        .checked_sub(median)
+        .or_else(|| median.checked_sub(price))
+        .unwrap_or(UsdPrice::MAX);
+    match (
+        deviation.checked_mul(UsdPrice::from(100_u8)),
+        median.checked_mul(UsdPrice::from(MAX_QUOTE_DEVIATION_PERCENT)),
+    ) {
+        (Some(deviation), Some(max_deviation)) => deviation <= max_deviation,
+        // Prices this large do not fit the comparison, they are never plausible
+        _ => false,
+    }
+}
+
+// Mean of the two middle prices for an even number of prices
+fn median(mut prices: Vec<UsdPrice>) -> Option<UsdPrice> {
+    prices.sort_unstable();
+    let middle = prices.len() / 2;
+    match prices.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(prices[middle]),
+        _ => {
+            let (low, high) = (prices[middle - 1], prices[middle]);
+            Some(low.checked_add(high).map_or(high, UsdPrice::div_by_two))
+        }
+    }
+}
+
+// Usd price of a token paired with a usd stablecoin in a constant product pool, the stablecoin is
+// assumed to be worth exactly one dollar
+pub fn pool_usd_price(
+    token_reserve: &Nat,
+    token_decimals: u8,
+    stable_reserve: &Nat,
+    stable_decimals: u8,
+) -> Option<UsdPrice> {
+    if token_reserve == &Nat::from(0_u8) {
+        return None;
+    }
+    let scale =
+        (USD_DECIMALS as u32 + token_decimals as u32).checked_sub(stable_decimals as u32)?;
+    let price = stable_reserve.0.clone() * Nat::from(10_u8).0.pow(scale) / token_reserve.0.clone();
+    UsdPrice::try_from(Nat(price)).ok()
+}
+
+// Usd price from a rate with `decimals` decimals
+pub fn scaled_usd_price(rate: u64, decimals: u32) -> Option<UsdPrice> {
+    let price = match USD_DECIMALS as u32 >= decimals {
+        true => Nat::from(rate).0 * Nat::from(10_u8).0.pow(USD_DECIMALS as u32 - decimals),
+        false => Nat::from(rate).0 / Nat::from(10_u8).0.pow(decimals - USD_DECIMALS as u32),
+    };
+    UsdPrice::try_from(Nat(price)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_000_000_000;
+
+    fn quote(source: PriceSource, ledger_id: Principal, usd_price: u64, age: u64) -> PriceQuote {
+        PriceQuote {
+            source,
+            ledger_id,
+            usd_price: UsdPrice::from(usd_price),
+            timestamp: NOW - age,
+        }
+    }
+
+    #[test]
+    fn should_take_median_of_quotes_within_deviation() {
+        let ledger_id = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let prices = aggregate_usd_prices(
+            vec![
+                quote(PriceSource::IcpSwap, ledger_id, 1_000, 0),
+                quote(PriceSource::Sonic, ledger_id, 1_100, 0),
+                quote(PriceSource::KongSwap, ledger_id, 1_040, 0),
+                // Far off the median of 1_070
+                quote(PriceSource::ExchangeRateCanister, ledger_id, 5_000, 0),
+            ],
+            NOW,
+        );
+
+        assert_eq!(prices.get(&ledger_id), Some(&UsdPrice::from(1_040_u64)));
+    }
+
+    #[test]
+    fn should_ignore_stale_and_zero_quotes() {
+        let fresh = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let stale = Principal::from_text("mxzaz-hqaaa-aaaar-qaada-cai").unwrap();
+        let prices = aggregate_usd_prices(
+            vec![
+                quote(PriceSource::IcpSwap, fresh, 1_000, MAX_QUOTE_AGE),
+                quote(PriceSource::Sonic, fresh, 2_000, MAX_QUOTE_AGE + 1),
+                quote(PriceSource::KongSwap, fresh, 0, 0),
+                quote(PriceSource::IcpSwap, stale, 1_000, MAX_QUOTE_AGE + 1),
+            ],
+            NOW,
+        );
+
+        assert_eq!(prices, BTreeMap::from([(fresh, UsdPrice::from(1_000_u64))]));
+    }
+
+    #[test]
+    fn should_price_tokens_from_stablecoin_pool_reserves() {
+        // 2_000 tokens with 8 decimals against 500 usd with 6 decimals
+        assert_eq!(
+            pool_usd_price(
+                &Nat::from(200_000_000_000_u64),
+                8,
+                &Nat::from(500_000_000_u64),
+                6
+            ),
+            Some(UsdPrice::from(250_000_000_000_000_000_u64))
+        );
+        assert_eq!(
+            pool_usd_price(&Nat::from(0_u8), 8, &Nat::from(500_000_000_u64), 6),
+            None
+        );
+        assert_eq!(
+            scaled_usd_price(12_345_000_000, 9),
+            Some(UsdPrice::from(12_345_000_000_000_000_000_u128))
+        );
+    }
+
+    #[test]
+    fn should_not_price_tokens_whose_quotes_disagree() {
+        let ledger_id = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let prices = aggregate_usd_prices(
+            vec![
+                quote(PriceSource::IcpSwap, ledger_id, 1_000, 0),
+                quote(PriceSource::Sonic, ledger_id, 3_000, 0),
+            ],
+            NOW,
+        );
+
+        assert!(prices.is_empty());
+    }
+}
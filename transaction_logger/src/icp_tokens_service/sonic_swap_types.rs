@@ -5,7 +5,8 @@ use candid::{self, CandidType, Decode, Deserialize, Encode, Principal};
 use ic_cdk::api::call::CallResult as Result;
 
 use crate::{
-    numeric::Erc20TokenAmount,
+    icp_tokens_service::ICP_TOKEN_LOGO_BASE_URL,
+    numeric::{Erc20TokenAmount, UsdPrice},
     state::{checked_nat_to_erc20_amount, checked_nat_to_u64, nat_to_u64, IcpToken, IcpTokenType},
 };
 
@@ -41,17 +42,31 @@ impl From<TokenInfoWithType> for IcpToken {
             decimals: value.decimals,
             symbol: value.symbol,
             token_type,
-            logo: format!(
-                "https://wqfao-piaaa-aaaag-qj5ba-cai.raw.icp0.io/{}",
-                ledger_id
-            ),
-            usd_price: "0".to_string(),
+            logo: format!("{}{}", ICP_TOKEN_LOGO_BASE_URL, ledger_id),
+            usd_price: UsdPrice::ZERO,
             fee: checked_nat_to_erc20_amount(value.fee).unwrap_or(Erc20TokenAmount::ZERO),
             rank: None,
         }
     }
 }
 
+#[allow(non_snake_case)]
+#[derive(CandidType, Deserialize, Debug)]
+pub struct PairInfoExt {
+    pub id: String,
+    pub price0CumulativeLast: candid::Nat,
+    pub creator: Principal,
+    pub reserve0: candid::Nat,
+    pub reserve1: candid::Nat,
+    pub lptoken: String,
+    pub totalSupply: candid::Nat,
+    pub token0: String,
+    pub token1: String,
+    pub price1CumulativeLast: candid::Nat,
+    pub kLast: candid::Nat,
+    pub blockTimestampLast: candid::Int,
+}
+
 pub struct Service(pub Principal);
 impl Service {
     pub async fn get_supported_token_list(&self) -> Result<(Vec<TokenInfoWithType>,)> {
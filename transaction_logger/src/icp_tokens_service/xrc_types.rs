@@ -0,0 +1,69 @@
+// Subset of the exchange rate canister interface used for pricing tokens
+use candid::{self, CandidType, Deserialize};
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum AssetClass {
+    Cryptocurrency,
+    FiatCurrency,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Asset {
+    pub symbol: String,
+    pub class: AssetClass,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub struct GetExchangeRateRequest {
+    pub base_asset: Asset,
+    pub quote_asset: Asset,
+    // Seconds since the unix epoch, the latest rate is returned if None
+    pub timestamp: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub struct ExchangeRateMetadata {
+    pub decimals: u32,
+    pub base_asset_num_queried_sources: u64,
+    pub base_asset_num_received_rates: u64,
+    pub quote_asset_num_queried_sources: u64,
+    pub quote_asset_num_received_rates: u64,
+    pub standard_deviation: u64,
+    pub forex_timestamp: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub struct ExchangeRate {
+    pub base_asset: Asset,
+    pub quote_asset: Asset,
+    // Seconds since the unix epoch
+    pub timestamp: u64,
+    pub rate: u64,
+    pub metadata: ExchangeRateMetadata,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub enum ExchangeRateError {
+    AnonymousPrincipalNotAllowed,
+    Pending,
+    CryptoBaseAssetNotFound,
+    CryptoQuoteAssetNotFound,
+    StablecoinRateNotFound,
+    StablecoinRateTooFewRates,
+    StablecoinRateZeroRate,
+    ForexInvalidTimestamp,
+    ForexBaseAssetNotFound,
+    ForexQuoteAssetNotFound,
+    ForexAssetsNotFound,
+    RateLimited,
+    NotEnoughCycles,
+    FailedToAcceptCycles,
+    InconsistentRatesReceived,
+    Other { code: u32, description: String },
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub enum GetExchangeRateResult {
+    Ok(ExchangeRate),
+    Err(ExchangeRateError),
+}
@@ -13,8 +13,8 @@ pub mod lsm_types;
 // Dfinity
 pub mod lso_types;
 
-pub struct LsClient {
-    pub runtime: IcRunTime,
+pub struct LsClient<R: Runtime = IcRunTime> {
+    pub runtime: R,
     pub id: Principal,
     pub operator: Operator,
 }
@@ -29,8 +29,14 @@ impl EvmIcpBridgePairs {
 
 impl LsClient {
     pub fn new(id: Principal, operator: Operator) -> Self {
+        Self::with_runtime(IcRunTime(), id, operator)
+    }
+}
+
+impl<R: Runtime> LsClient<R> {
+    pub fn with_runtime(runtime: R, id: Principal, operator: Operator) -> Self {
         Self {
-            runtime: IcRunTime(),
+            runtime,
             id,
             operator,
         }
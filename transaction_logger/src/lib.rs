@@ -1,24 +1,38 @@
 use std::time::Duration;
 
 pub mod add_evm_tokens;
+pub mod bridge_stats;
 pub mod checked_amount;
+pub mod dashboard;
 pub mod endpoints;
 pub mod guard;
+pub mod http;
 pub mod icp_tokens_service;
 pub mod ledger_manager_client;
 pub mod lifecycle;
 pub mod logs;
+pub mod metrics;
 pub mod minter_clinet;
 pub mod numeric;
+pub mod price_history;
 pub mod remove_unverified_tx;
 pub mod scrape_events;
 pub mod state;
 pub mod update_bridge_pairs;
 pub mod update_icp_tokens;
 
-// 1 Minute
+// 10 Seconds, checks which minters are due for scraping
+pub const SCHEDULE_SCRAPE_EVENTS: Duration = Duration::from_secs(10);
+
+// 1 Minute, default interval between two scraping rounds of a minter
 pub const SCRAPE_EVENTS: Duration = Duration::from_secs(1 * 60);
 
+// 1 Day, longest interval that can be set for a minter
+pub const MAX_SCRAPE_EVENTS_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// 5 Minutes
+pub const RETRY_FAILED_SCRAPE_RANGES: Duration = Duration::from_secs(5 * 60);
+
 // 5 Muntes
 pub const UPDATE_USD_PRICE: Duration = Duration::from_secs(10 * 60);
 
@@ -28,6 +42,9 @@ pub const REMOVE_UNVERIFIED_TX: Duration = Duration::from_secs(24 * 60 * 60);
 // 1 Day
 pub const UPDATE_BRIDGE_PAIRS: Duration = Duration::from_secs(24 * 60 * 60);
 
+// 5 Seconds, bridge pair updates requested within it run once
+pub const REQUESTED_UPDATE_BRIDGE_PAIRS_DELAY: Duration = Duration::from_secs(5);
+
 // 1 Day
 pub const UPDATE_ICP_TOKENS: Duration = Duration::from_secs(24 * 60 * 60);
 
@@ -21,6 +21,9 @@ pub fn init(init_args: InitArgs) {
 }
 
 pub fn post_upgrade(upgrade_arg: Option<UpgradeArg>) {
+    // Transactions recorded before the secondary indexes existed are indexed by timers
+    mutate_state(|s| s.start_index_backfill());
+
     if let Some(args) = upgrade_arg {
         log!(INFO, "[upgrade]: upgrading logger with arg: {:?}", args);
 
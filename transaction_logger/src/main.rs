@@ -2,37 +2,64 @@ use std::borrow::Borrow;
 use std::str::FromStr;
 use std::time::Duration;
 
-use candid::Principal;
+use candid::{Nat, Principal};
 use ic_canister_log::log;
 use ic_cdk::{init, post_upgrade, query, update};
 use ic_cdk_timers;
 use ic_ethereum_types::Address;
-use transaction_logger::add_evm_tokens::add_evm_tokens_to_state;
+use transaction_logger::add_evm_tokens::{add_evm_tokens_to_state, parse_evm_tokens_json};
+use transaction_logger::dashboard::{DashboardData, DASHBOARD_RECENT_TRANSACTIONS};
 use transaction_logger::endpoints::{
     AddEvmToIcpTx, AddEvmToIcpTxError, AddIcpToEvmTx, AddIcpToEvmTxError,
-    CandidAddErc20TwinLedgerSuiteRequest, CandidEvmToken, CandidIcpToken, CandidLedgerSuiteRequest,
-    GetEvmTokenArgs, GetIcpTokenArgs, GetTxParams, Icrc28TrustedOriginsResponse, TokenPair,
-    Transaction,
+    CandidAddErc20TwinLedgerSuiteRequest, CandidBridgeStats, CandidDailyUsdVolume,
+    CandidDepositEta, CandidEvmToken, CandidFailedScrapeRange, CandidIcpToken,
+    CandidLedgerSuiteRequest, CandidMinterSyncStatus, CandidPricePoint, CandidTimelineEntry,
+    CandidTransactionAttempt, CandidTransferUsdValue, EvmTokenArgs, GetEvmTokenArgs,
+    GetIcpTokenArgs, GetTokenPriceHistoryArgs, GetTxParams, GetTxsByAddressArgs,
+    GetTxsByPrincipalArgs, GetTxsError, GetUsdVolumeArgs, Icrc28TrustedOriginsResponse,
+    ManageEvmTokenError, ManageMinterError, MinterArgs, MinterKeyArgs, MinterStatus,
+    RescanMinterEventsArgs, RescanMinterEventsError, ResetLastScrapedEventArgs,
+    SetEvmTokenHiddenArgs, StatsWindow, TokenPair, Transaction, TransactionsPage,
+    UpdateEvmTokenPriceArgs, UpdateEvmTokenPriceError, UpdateMinterArgs,
+    UpdateMinterScrapeIntervalArgs,
 };
 use transaction_logger::guard::{TaskType, TimerGuard};
+use transaction_logger::http::{HttpRequest, HttpResponse, HttpResponseBuilder};
 use transaction_logger::lifecycle::{self, init as initialize};
+use transaction_logger::logs::{Log, Priority, Sort};
+use transaction_logger::minter_clinet::MinterClient;
+use transaction_logger::numeric::UsdPrice;
+use transaction_logger::scrape_events::{rescan_events_range, retry_failed_scrape_ranges};
 use transaction_logger::state::{
-    mutate_state, nat_to_erc20_amount, nat_to_ledger_burn_index, nat_to_u64, read_state, ChainId,
-    Erc20Identifier, Erc20TwinLedgerSuiteRequest, EvmToIcpStatus, EvmToIcpTx, EvmToIcpTxIdentifier,
-    IcpToEvmIdentifier, IcpToEvmStatus, IcpToEvmTx, IcpToken,
+    checked_nat_to_erc20_amount, checked_nat_to_u64, mutate_state, nat_to_erc20_amount,
+    nat_to_ledger_burn_index, nat_to_u64, read_state, ChainId, Erc20Identifier,
+    Erc20TwinLedgerSuiteRequest, EvmToIcpStatus, EvmToIcpTx, EvmToIcpTxIdentifier, EvmToken,
+    IcpToEvmIdentifier, IcpToEvmStatus, IcpToEvmTx, IcpToken, Minter, MinterKey, TransactionCursor,
+    TransactionKey, INDEX_BACKFILL_CHUNK_SIZE, MAX_TRANSACTIONS_PER_PAGE,
+};
+use transaction_logger::update_bridge_pairs::{
+    request_bridge_pairs_refresh, update_requested_bridge_pairs, BridgePairsRefresh,
+    APPIC_LEDGER_MANAGER_ID,
 };
-use transaction_logger::update_bridge_pairs::APPIC_LEDGER_MANAGER_ID;
 use transaction_logger::update_icp_tokens::{update_icp_tokens, update_usd_price, validate_tokens};
 use transaction_logger::{
     endpoints::LoggerArgs, logs::INFO, remove_unverified_tx::remove_unverified_tx,
     scrape_events::scrape_events, update_bridge_pairs::update_bridge_pairs, REMOVE_UNVERIFIED_TX,
-    SCRAPE_EVENTS, UPDATE_BRIDGE_PAIRS,
+    SCHEDULE_SCRAPE_EVENTS, UPDATE_BRIDGE_PAIRS,
+};
+use transaction_logger::{
+    MAX_SCRAPE_EVENTS_INTERVAL, REMOVE_INVALID_ICP_TOKENS, REQUESTED_UPDATE_BRIDGE_PAIRS_DELAY,
+    RETRY_FAILED_SCRAPE_RANGES, UPDATE_ICP_TOKENS, UPDATE_USD_PRICE,
 };
-use transaction_logger::{REMOVE_INVALID_ICP_TOKENS, UPDATE_ICP_TOKENS, UPDATE_USD_PRICE};
 // Setup timers
 fn setup_timers() {
-    // Start scraping events.
-    ic_cdk_timers::set_timer_interval(SCRAPE_EVENTS, || ic_cdk::spawn(scrape_events()));
+    // Start scraping events, every minter is scraped on its own interval
+    ic_cdk_timers::set_timer_interval(SCHEDULE_SCRAPE_EVENTS, || ic_cdk::spawn(scrape_events()));
+
+    // Retry event ranges that were skipped while scraping
+    ic_cdk_timers::set_timer_interval(RETRY_FAILED_SCRAPE_RANGES, || {
+        ic_cdk::spawn(retry_failed_scrape_ranges())
+    });
 
     // Update usd price of icp tokens
     ic_cdk_timers::set_timer_interval(UPDATE_USD_PRICE, || ic_cdk::spawn(update_usd_price()));
@@ -103,6 +130,13 @@ fn post_upgrade(upgrade_args: Option<LoggerArgs>) {
         None => lifecycle::post_upgrade(None),
     }
 
+    // Add tokens new to the bundled lists, keeping the ones edited at runtime
+    add_evm_tokens_to_state();
+
+    if read_state(|s| s.is_index_backfill_pending()) {
+        schedule_index_backfill();
+    }
+
     // Set up timers
     setup_timers();
 }
@@ -238,6 +272,83 @@ pub fn get_txs_by_principal(principal_id: Principal) -> Vec<Transaction> {
     read_state(|s| s.get_transaction_for_principal(principal_id))
 }
 
+#[query]
+pub fn get_txs_by_address_paginated(
+    args: GetTxsByAddressArgs,
+) -> Result<TransactionsPage, GetTxsError> {
+    let address =
+        Address::from_str(&args.address).map_err(|e| GetTxsError::InvalidAddress(e.to_string()))?;
+    let cursor = parse_transaction_cursor(args.cursor)?;
+    let limit = args.limit.clamp(1, MAX_TRANSACTIONS_PER_PAGE);
+    Ok(read_state(|s| {
+        s.get_transactions_page_for_address(address, cursor, limit)
+    }))
+}
+
+#[query]
+pub fn get_txs_by_principal_paginated(
+    args: GetTxsByPrincipalArgs,
+) -> Result<TransactionsPage, GetTxsError> {
+    let cursor = parse_transaction_cursor(args.cursor)?;
+    let limit = args.limit.clamp(1, MAX_TRANSACTIONS_PER_PAGE);
+    Ok(read_state(|s| {
+        s.get_transactions_page_for_principal(args.principal_id, cursor, limit)
+    }))
+}
+
+fn parse_transaction_cursor(
+    cursor: Option<String>,
+) -> Result<Option<TransactionCursor>, GetTxsError> {
+    cursor
+        .map(|cursor| TransactionCursor::from_str(&cursor).map_err(GetTxsError::InvalidCursor))
+        .transpose()
+}
+
+// Scraping progress and health of every minter
+#[query]
+pub fn get_minters() -> Vec<MinterStatus> {
+    read_state(|s| {
+        s.get_minters()
+            .into_iter()
+            .map(|(minter_key, minter)| {
+                MinterStatus::from((
+                    minter,
+                    s.get_scrape_health(&minter_key),
+                    s.is_minter_enabled(&minter_key),
+                    s.get_scrape_interval(&minter_key),
+                ))
+            })
+            .collect()
+    })
+}
+
+// Event ranges skipped by the scraper that are waiting for a retry
+#[query]
+pub fn get_failed_scrape_ranges() -> Vec<CandidFailedScrapeRange> {
+    read_state(|s| {
+        s.get_failed_scrape_ranges()
+            .into_iter()
+            .map(CandidFailedScrapeRange::from)
+            .collect()
+    })
+}
+
+// Evm blocks a minter has synced to and skipped
+#[query]
+pub fn get_minter_sync_status(args: MinterKeyArgs) -> Option<CandidMinterSyncStatus> {
+    let minter_key = MinterKey::from(&args);
+
+    read_state(|s| {
+        s.minters.contains_key(&minter_key).then(|| {
+            CandidMinterSyncStatus::from((
+                minter_key.clone(),
+                s.get_synced_blocks(&minter_key),
+                s.get_skipped_blocks(&minter_key),
+            ))
+        })
+    })
+}
+
 #[query]
 pub fn get_bridge_pairs() -> Vec<TokenPair> {
     read_state(|s| s.get_suported_bridge_pairs())
@@ -259,6 +370,77 @@ pub fn get_transaction(params: GetTxParams) -> Option<Transaction> {
     search_result
 }
 
+// Every status the transaction went through, ordered by minter event index
+#[query]
+pub fn get_transaction_timeline(params: GetTxParams) -> Vec<CandidTimelineEntry> {
+    let chain_id = ChainId::from(&params.chain_id);
+
+    read_state(|s| {
+        s.get_transaction_key_by_search_params(params.search_param, chain_id)
+            .map(|key| {
+                s.get_transaction_timeline(&key)
+                    .into_iter()
+                    .map(CandidTimelineEntry::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
+// Estimated mint time of a pending or accepted deposit
+#[query]
+pub fn get_deposit_eta(params: GetTxParams) -> Option<CandidDepositEta> {
+    let chain_id = ChainId::from(&params.chain_id);
+
+    read_state(
+        |s| match s.get_transaction_key_by_search_params(params.search_param, chain_id) {
+            Some(TransactionKey::EvmToIcp(identifier)) => s.estimate_deposit_mint_time(&identifier),
+            _ => None,
+        },
+    )
+}
+
+// Volume, latency and failure statistics of every bridged token over the window
+#[query]
+pub fn get_bridge_stats(window: StatsWindow) -> Vec<CandidBridgeStats> {
+    read_state(|s| s.get_bridge_stats(window, ic_cdk::api::time()))
+}
+
+// Usd value of a transfer when it was accepted, None if its token had no known price
+#[query]
+pub fn get_transfer_usd_value(params: GetTxParams) -> Option<CandidTransferUsdValue> {
+    let chain_id = ChainId::from(&params.chain_id);
+
+    read_state(|s| {
+        s.get_transaction_key_by_search_params(params.search_param, chain_id)
+            .and_then(|key| s.get_transfer_usd_value(&key))
+            .map(CandidTransferUsdValue::from)
+    })
+}
+
+// Daily usd volume of every bridge pair and direction
+#[query]
+pub fn get_usd_volume(args: GetUsdVolumeArgs) -> Vec<CandidDailyUsdVolume> {
+    read_state(|s| s.get_usd_volumes(args.from, args.to))
+}
+
+// Every transaction sent for a withdrawal, replaced ones included, ordered by minter event index
+#[query]
+pub fn get_transaction_attempts(params: GetTxParams) -> Vec<CandidTransactionAttempt> {
+    let chain_id = ChainId::from(&params.chain_id);
+
+    read_state(
+        |s| match s.get_transaction_key_by_search_params(params.search_param, chain_id) {
+            Some(TransactionKey::IcpToEvm(identifier)) => s
+                .get_transaction_attempts(&identifier)
+                .into_iter()
+                .map(CandidTransactionAttempt::from)
+                .collect(),
+            _ => vec![],
+        },
+    )
+}
+
 #[query]
 pub fn get_evm_token(args: GetEvmTokenArgs) -> Option<CandidEvmToken> {
     // Validate address and create identifier
@@ -267,11 +449,12 @@ pub fn get_evm_token(args: GetEvmTokenArgs) -> Option<CandidEvmToken> {
         ChainId::from(&args.chain_id),
     );
 
-    // Get token from state
-    let token = read_state(|s| s.get_evm_token_by_identifier(&identifier))?;
-
-    // Return Token
-    Some(CandidEvmToken::from(token))
+    // Get token from state with its usd price, hidden tokens are not listed
+    read_state(|s| {
+        s.get_evm_token_by_identifier(&identifier)
+            .filter(|_token| !s.is_evm_token_hidden(&identifier))
+            .map(|token| s.get_candid_evm_token(token))
+    })
 }
 
 #[query]
@@ -283,6 +466,12 @@ pub fn get_icp_token(args: GetIcpTokenArgs) -> Option<CandidIcpToken> {
     Some(CandidIcpToken::from(token))
 }
 
+// Usd prices of an icp token, hourly for the last 30 days and daily before
+#[query]
+pub fn get_token_price_history(args: GetTokenPriceHistoryArgs) -> Vec<CandidPricePoint> {
+    read_state(|s| s.get_token_price_history(args.ledger_id, args.from, args.to))
+}
+
 #[update]
 // Can only be called by lsm
 pub fn add_icp_token(token: CandidIcpToken) {
@@ -336,11 +525,416 @@ pub fn update_twin_ls_request(updated_request: CandidAddErc20TwinLedgerSuiteRequ
 }
 
 // Can only be called by lsm
+// Schedules an update of the bridge pairs of the given tokens, or of every token if None
 #[update]
-pub fn request_update_bridge_pairs() {
+pub fn request_update_bridge_pairs(tokens: Option<Vec<GetEvmTokenArgs>>) {
     if ic_cdk::caller() != Principal::from_text(APPIC_LEDGER_MANAGER_ID).unwrap() {
         panic!("Endpoint can only be called by appic lsm");
     }
+
+    // Tokens with an invalid address are skipped, the others are still refreshed
+    let refresh = match tokens {
+        Some(tokens) => {
+            let identifiers: Vec<Erc20Identifier> = tokens
+                .iter()
+                .filter_map(
+                    |token| match evm_token_identifier(&token.address, &token.chain_id) {
+                        Ok(identifier) => Some(identifier),
+                        Err(err) => {
+                            log!(
+                                INFO,
+                                "[Scrape new twin tokens] Skipping requested token: {:?}",
+                                err
+                            );
+                            None
+                        }
+                    },
+                )
+                .collect();
+            if identifiers.is_empty() {
+                return;
+            }
+            BridgePairsRefresh::Tokens(identifiers.into_iter().collect())
+        }
+        None => BridgePairsRefresh::All,
+    };
+
+    if request_bridge_pairs_refresh(refresh) {
+        schedule_requested_bridge_pairs_update();
+    }
+}
+
+// Existing transactions are indexed a chunk per message, so a large history does not exceed the
+// instruction limit of the upgrade
+fn schedule_index_backfill() {
+    ic_cdk_timers::set_timer(Duration::from_secs(0), || {
+        if mutate_state(|s| s.index_existing_transactions(INDEX_BACKFILL_CHUNK_SIZE)) {
+            schedule_index_backfill();
+        } else {
+            log!(
+                INFO,
+                "[upgrade]: indexed transactions recorded before the indexes existed"
+            );
+        }
+    });
+}
+
+// Retries after the delay again while another update of the bridge pairs is running
+fn schedule_requested_bridge_pairs_update() {
+    ic_cdk_timers::set_timer(REQUESTED_UPDATE_BRIDGE_PAIRS_DELAY, || {
+        ic_cdk::spawn(async {
+            if update_requested_bridge_pairs().await {
+                schedule_requested_bridge_pairs_update();
+            }
+        })
+    });
+}
+
+fn ensure_controller() {
+    if !ic_cdk::api::is_controller(&ic_cdk::caller()) {
+        panic!("Endpoint can only be called by controllers");
+    }
+}
+
+fn ensure_controller_or_lsm() {
+    let caller = ic_cdk::caller();
+    if !ic_cdk::api::is_controller(&caller)
+        && caller != Principal::from_text(APPIC_LEDGER_MANAGER_ID).unwrap()
+    {
+        panic!("Endpoint can only be called by controllers or appic lsm");
+    }
+}
+
+fn evm_token_identifier(
+    address: &str,
+    chain_id: &Nat,
+) -> Result<Erc20Identifier, ManageEvmTokenError> {
+    let address = Address::from_str(address)
+        .map_err(|_| ManageEvmTokenError::InvalidAddress(address.to_string()))?;
+    Ok(Erc20Identifier::new(&address, ChainId::from(chain_id)))
+}
+
+// Can only be called by controllers or lsm
+#[update]
+pub fn add_evm_token(args: EvmTokenArgs) -> Result<(), ManageEvmTokenError> {
+    ensure_controller_or_lsm();
+
+    let token = EvmToken::try_from(args)?;
+    if read_state(|s| s.get_evm_token_by_identifier(&Erc20Identifier::from(&token))).is_some() {
+        return Err(ManageEvmTokenError::TokenAlreadyExists);
+    }
+
+    log!(INFO, "[Token Registry] Adding evm token {:?}", token);
+    mutate_state(|s| s.upsert_evm_token(token));
+
+    Ok(())
+}
+
+// Can only be called by controllers or lsm
+#[update]
+pub fn update_evm_token(args: EvmTokenArgs) -> Result<(), ManageEvmTokenError> {
+    ensure_controller_or_lsm();
+
+    let token = EvmToken::try_from(args)?;
+    if read_state(|s| s.get_evm_token_by_identifier(&Erc20Identifier::from(&token))).is_none() {
+        return Err(ManageEvmTokenError::TokenNotFound);
+    }
+
+    log!(INFO, "[Token Registry] Updating evm token {:?}", token);
+    mutate_state(|s| s.upsert_evm_token(token));
+
+    Ok(())
+}
+
+// Can only be called by controllers or lsm
+// Hidden tokens are not returned by get_evm_token but are still used to process transactions
+#[update]
+pub fn set_evm_token_hidden(args: SetEvmTokenHiddenArgs) -> Result<(), ManageEvmTokenError> {
+    ensure_controller_or_lsm();
+
+    let identifier = evm_token_identifier(&args.address, &args.chain_id)?;
+
+    log!(
+        INFO,
+        "[Token Registry] Setting hidden of evm token {:?} to {}",
+        identifier,
+        args.hidden
+    );
+    match mutate_state(|s| s.set_evm_token_hidden(&identifier, args.hidden)) {
+        true => Ok(()),
+        false => Err(ManageEvmTokenError::TokenNotFound),
+    }
+}
+
+// Can only be called by controllers or lsm
+// Deleted tokens are not added back by upgrades
+#[update]
+pub fn delete_evm_token(args: GetEvmTokenArgs) -> Result<(), ManageEvmTokenError> {
+    ensure_controller_or_lsm();
+
+    let identifier = evm_token_identifier(&args.address, &args.chain_id)?;
+
+    log!(INFO, "[Token Registry] Deleting evm token {:?}", identifier);
+    mutate_state(|s| s.delete_evm_token(&identifier))
+        .map(|_token| ())
+        .ok_or(ManageEvmTokenError::TokenNotFound)
+}
+
+// Can only be called by controllers or lsm
+// Adds or updates every token of a json list in the format of the bundled token lists, returns
+// the number of tokens imported
+#[update]
+pub fn import_evm_tokens(json: String) -> Result<u64, ManageEvmTokenError> {
+    ensure_controller_or_lsm();
+
+    let tokens = parse_evm_tokens_json(&json).map_err(ManageEvmTokenError::InvalidJson)?;
+    let imported = tokens.len() as u64;
+
+    log!(INFO, "[Token Registry] Importing {} evm tokens", imported);
+    mutate_state(|s| {
+        for token in tokens {
+            s.upsert_evm_token(token);
+        }
+    });
+
+    Ok(imported)
+}
+
+// Can only be called by controllers
+#[update]
+pub fn add_minter(args: MinterArgs) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey(ChainId::from(&args.chain_id), args.operator);
+    if read_state(|s| s.if_minter_exists(&minter_key)) {
+        return Err(ManageMinterError::MinterAlreadyExists);
+    }
+
+    checked_nat_to_erc20_amount(args.evm_to_icp_fee.clone())
+        .and(checked_nat_to_erc20_amount(args.icp_to_evm_fee.clone()))
+        .ok_or(ManageMinterError::InvalidFee)?;
+    checked_nat_to_u64(&args.last_observed_event)
+        .and(checked_nat_to_u64(&args.last_scraped_event))
+        .ok_or(ManageMinterError::InvalidEventIndex)?;
+
+    log!(INFO, "[Admin] Adding minter: {:?}", args);
+    mutate_state(|s| s.record_minter(Minter::from_minter_args(args)));
+
+    Ok(())
+}
+
+// Can only be called by controllers
+// Prices tokens that have no priced icp twin, no price is updated if any of the args is invalid
+#[update]
+pub fn update_evm_token_prices(
+    args: Vec<UpdateEvmTokenPriceArgs>,
+) -> Result<(), UpdateEvmTokenPriceError> {
+    ensure_controller();
+
+    let mut prices = Vec::with_capacity(args.len());
+    for arg in args {
+        let address = Address::from_str(&arg.address)
+            .map_err(|_| UpdateEvmTokenPriceError::InvalidAddress(arg.address.clone()))?;
+        let identifier = Erc20Identifier::new(&address, ChainId::from(&arg.chain_id));
+        if read_state(|s| s.get_evm_token_by_identifier(&identifier)).is_none() {
+            return Err(UpdateEvmTokenPriceError::TokenNotFound(arg.address));
+        }
+        let usd_price = arg
+            .usd_price_e18
+            .map(|usd_price| {
+                UsdPrice::try_from(usd_price.clone())
+                    .map_err(|_| UpdateEvmTokenPriceError::InvalidUsdPrice(usd_price.to_string()))
+            })
+            .transpose()?;
+        prices.push((identifier, usd_price));
+    }
+
+    log!(
+        INFO,
+        "[Admin] Updating usd price of {} evm tokens",
+        prices.len()
+    );
+    let now = ic_cdk::api::time();
+    mutate_state(|s| {
+        for (identifier, usd_price) in prices {
+            s.set_evm_token_price(identifier, usd_price, now);
+        }
+    });
+
+    Ok(())
+}
+
+// Can only be called by controllers
+// Transactions scraped from the minter are kept
+#[update]
+pub fn remove_minter(args: MinterKeyArgs) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey::from(&args);
+    log!(INFO, "[Admin] Removing minter {:?}", minter_key);
+
+    mutate_state(|s| s.remove_minter(&minter_key))
+        .map(|_minter| ())
+        .ok_or(ManageMinterError::MinterNotFound)
+}
+
+// Can only be called by controllers
+#[update]
+pub fn disable_minter(args: MinterKeyArgs) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey::from(&args);
+    if !read_state(|s| s.if_minter_exists(&minter_key)) {
+        return Err(ManageMinterError::MinterNotFound);
+    }
+
+    log!(INFO, "[Admin] Disabling minter {:?}", minter_key);
+    mutate_state(|s| s.disable_minter(&minter_key));
+
+    Ok(())
+}
+
+// Can only be called by controllers
+#[update]
+pub fn enable_minter(args: MinterKeyArgs) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey::from(&args);
+    if !read_state(|s| s.if_minter_exists(&minter_key)) {
+        return Err(ManageMinterError::MinterNotFound);
+    }
+
+    log!(INFO, "[Admin] Enabling minter {:?}", minter_key);
+    mutate_state(|s| s.enable_minter(&minter_key));
+
+    Ok(())
+}
+
+// Can only be called by controllers
+#[update]
+pub fn update_minter_fees(args: UpdateMinterArgs) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey(ChainId::from(&args.chain_id), args.operator);
+    if !read_state(|s| s.if_minter_exists(&minter_key)) {
+        return Err(ManageMinterError::MinterNotFound);
+    }
+
+    let evm_to_icp_fee = checked_nat_to_erc20_amount(args.evm_to_icp_fee.clone())
+        .ok_or(ManageMinterError::InvalidFee)?;
+    let icp_to_evm_fee = checked_nat_to_erc20_amount(args.icp_to_evm_fee.clone())
+        .ok_or(ManageMinterError::InvalidFee)?;
+
+    log!(
+        INFO,
+        "[Admin] Updating minter {:?} with args: {:?}",
+        minter_key,
+        args
+    );
+    mutate_state(|s| s.update_minter_fees(&minter_key, evm_to_icp_fee, icp_to_evm_fee));
+
+    Ok(())
+}
+
+// Can only be called by controllers
+#[update]
+pub fn update_minter_scrape_interval(
+    args: UpdateMinterScrapeIntervalArgs,
+) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey(ChainId::from(&args.chain_id), args.operator);
+    if !read_state(|s| s.if_minter_exists(&minter_key)) {
+        return Err(ManageMinterError::MinterNotFound);
+    }
+
+    if args.interval_seconds == 0 || args.interval_seconds > MAX_SCRAPE_EVENTS_INTERVAL.as_secs() {
+        return Err(ManageMinterError::InvalidScrapeInterval);
+    }
+
+    log!(
+        INFO,
+        "[Admin] Setting scrape interval of minter {:?} to {} seconds",
+        minter_key,
+        args.interval_seconds
+    );
+    mutate_state(|s| s.set_scrape_interval(&minter_key, args.interval_seconds));
+
+    Ok(())
+}
+
+// Can only be called by controllers
+// Events after last_scraped_event are scraped again in the next round
+#[update]
+pub fn reset_last_scraped_event(args: ResetLastScrapedEventArgs) -> Result<(), ManageMinterError> {
+    ensure_controller();
+
+    let minter_key = MinterKey(ChainId::from(&args.chain_id), args.operator);
+    let last_observed_event = read_state(|s| s.minters.get(&minter_key))
+        .ok_or(ManageMinterError::MinterNotFound)?
+        .last_observed_event;
+
+    let last_scraped_event =
+        checked_nat_to_u64(&args.last_scraped_event).ok_or(ManageMinterError::InvalidEventIndex)?;
+
+    // Events past the last observed one do not exist yet, skipping them would lose them
+    if last_scraped_event > last_observed_event {
+        return Err(ManageMinterError::EventNotObserved {
+            last_observed_event,
+        });
+    }
+
+    log!(
+        INFO,
+        "[Admin] Resetting last scraped event of minter {:?} to {}",
+        minter_key,
+        last_scraped_event
+    );
+    mutate_state(|s| s.update_last_scraped_event(&minter_key, last_scraped_event));
+
+    Ok(())
+}
+
+// Can only be called by controllers
+// Reapplies a range of minter events, e.g. after a range was skipped or misprocessed
+#[update]
+pub async fn rescan_minter_events(
+    args: RescanMinterEventsArgs,
+) -> Result<(), RescanMinterEventsError> {
+    ensure_controller();
+
+    if args.from_event > args.to_event {
+        return Err(RescanMinterEventsError::InvalidRange);
+    }
+
+    let minter_key = MinterKey(ChainId::from(&args.chain_id), args.operator);
+    let minter = read_state(|s| s.minters.get(&minter_key))
+        .ok_or(RescanMinterEventsError::MinterNotFound)?;
+
+    let _guard = TimerGuard::new(TaskType::RescanEvents)
+        .map_err(|_| RescanMinterEventsError::AlreadyRescanning)?;
+
+    log!(
+        INFO,
+        "[Admin] Rescanning events from {} to {} minter {:?}",
+        args.from_event,
+        args.to_event,
+        minter_key
+    );
+
+    rescan_events_range(
+        &MinterClient::from(&minter),
+        &minter_key,
+        args.from_event,
+        args.to_event,
+        minter.evm_to_icp_fee,
+        minter.icp_to_evm_fee,
+    )
+    .await
+    .map_err(|(next_event, err)| RescanMinterEventsError::ScrapeFailed {
+        next_event,
+        reason: err.to_string(),
+    })
 }
 
 #[query]
@@ -349,6 +943,108 @@ pub fn get_erc20_twin_ls_reqests_by_creator(creator: Principal) -> Vec<CandidLed
     requests.into_iter().map(|request| request.into()).collect()
 }
 
+#[query(hidden = true)]
+fn http_request(req: HttpRequest) -> HttpResponse {
+    if ic_cdk::api::data_certificate().is_none() {
+        ic_cdk::trap("update call rejected");
+    }
+
+    match req.path() {
+        "/metrics" => {
+            let mut writer = ic_metrics_encoder::MetricsEncoder::new(
+                vec![],
+                (ic_cdk::api::time() / 1_000_000) as i64,
+            );
+
+            match transaction_logger::metrics::encode_metrics(&mut writer) {
+                Ok(()) => HttpResponseBuilder::ok()
+                    .header("Content-Type", "text/plain; version=0.0.4")
+                    .header("Cache-Control", "no-store")
+                    .with_body_and_content_length(writer.into_inner())
+                    .build(),
+                Err(err) => {
+                    HttpResponseBuilder::server_error(format!("Failed to encode metrics: {}", err))
+                        .build()
+                }
+            }
+        }
+        "/logs" => {
+            const MAX_BODY_SIZE: usize = 2_000_000;
+
+            let max_skip_timestamp = match req.raw_query_param("time") {
+                Some(arg) => match u64::from_str(arg) {
+                    Ok(value) => value,
+                    Err(_) => {
+                        return HttpResponseBuilder::bad_request()
+                            .with_body_and_content_length("failed to parse the 'time' parameter")
+                            .build()
+                    }
+                },
+                None => 0,
+            };
+
+            let mut log: Log = Default::default();
+
+            match req.raw_query_param("priority") {
+                Some(priority_str) => match Priority::from_str(priority_str) {
+                    Ok(priority) => log.push_logs(priority),
+                    Err(_) => log.push_all(),
+                },
+                None => log.push_all(),
+            }
+
+            log.entries
+                .retain(|entry| entry.timestamp >= max_skip_timestamp);
+
+            fn ordering_from_query_params(sort: Option<&str>, max_skip_timestamp: u64) -> Sort {
+                match sort {
+                    Some(ord_str) => match Sort::from_str(ord_str) {
+                        Ok(order) => order,
+                        Err(_) => {
+                            if max_skip_timestamp == 0 {
+                                Sort::Ascending
+                            } else {
+                                Sort::Descending
+                            }
+                        }
+                    },
+                    None => {
+                        if max_skip_timestamp == 0 {
+                            Sort::Ascending
+                        } else {
+                            Sort::Descending
+                        }
+                    }
+                }
+            }
+
+            log.sort_logs(ordering_from_query_params(
+                req.raw_query_param("sort"),
+                max_skip_timestamp,
+            ));
+
+            HttpResponseBuilder::ok()
+                .header("Content-Type", "application/json; charset=utf-8")
+                .with_body_and_content_length(log.serialize_logs(MAX_BODY_SIZE))
+                .build()
+        }
+        "/dashboard" => {
+            let dashboard = read_state(|s| DashboardData {
+                minters: s.get_minters(),
+                evm_to_icp_count: s.evm_to_icp_txs.len(),
+                icp_to_evm_count: s.icp_to_evm_txs.len(),
+                recent_transactions: s.get_recent_transactions(DASHBOARD_RECENT_TRANSACTIONS),
+            });
+
+            HttpResponseBuilder::ok()
+                .header("Content-Type", "text/html; charset=utf-8")
+                .with_body_and_content_length(dashboard.render())
+                .build()
+        }
+        _ => HttpResponseBuilder::not_found().build(),
+    }
+}
+
 // list every base URL that users will authenticate to your app from
 #[update]
 fn icrc28_trusted_origins() -> Icrc28TrustedOriginsResponse {
@@ -0,0 +1,219 @@
+use ic_metrics_encoder::MetricsEncoder;
+
+use crate::state::{
+    read_state, stable_memory_sizes, EvmToIcpStatus, IcpToEvmStatus, Operator, State,
+    TimelineStatus,
+};
+
+const WASM_PAGE_SIZE_IN_BYTES: f64 = 65536.0;
+
+// Encodes canister metrics in the Prometheus text format
+pub fn encode_metrics(w: &mut MetricsEncoder<Vec<u8>>) -> std::io::Result<()> {
+    w.encode_gauge(
+        "cycle_balance",
+        ic_cdk::api::canister_balance128() as f64,
+        "Cycle balance of this canister.",
+    )?;
+
+    w.encode_gauge(
+        "stable_memory_bytes",
+        ic_cdk::api::stable::stable_size() as f64 * WASM_PAGE_SIZE_IN_BYTES,
+        "Size of the stable memory allocated by this canister.",
+    )?;
+
+    let mut stable_memory = w.gauge_vec(
+        "stable_memory_bytes_by_memory",
+        "Size of each virtual stable memory used by this canister.",
+    )?;
+    for (memory, pages) in stable_memory_sizes() {
+        stable_memory = stable_memory.value(
+            &[("memory", memory)],
+            pages as f64 * WASM_PAGE_SIZE_IN_BYTES,
+        )?;
+    }
+
+    w.encode_gauge(
+        "heap_memory_bytes",
+        heap_memory_size_bytes() as f64,
+        "Size of the heap memory allocated by this canister.",
+    )?;
+
+    read_state(|s| {
+        encode_minter_metrics(w, s)?;
+        encode_transaction_metrics(w, s)?;
+        encode_token_metrics(w, s)
+    })
+}
+
+fn encode_minter_metrics(w: &mut MetricsEncoder<Vec<u8>>, s: &State) -> std::io::Result<()> {
+    w.encode_gauge(
+        "minters",
+        s.minters.len() as f64,
+        "Number of minters whose events are scraped.",
+    )?;
+
+    let minters: Vec<(String, &str, u64, u64)> = s
+        .minters
+        .values()
+        .map(|minter| {
+            (
+                minter.chain_id.0.to_string(),
+                operator_label(minter.operator),
+                minter.last_observed_event,
+                minter.last_scraped_event,
+            )
+        })
+        .collect();
+
+    let mut last_observed = w.gauge_vec(
+        "minter_last_observed_event",
+        "Latest event index observed on the minter.",
+    )?;
+    for (chain_id, operator, last_observed_event, _) in &minters {
+        last_observed = last_observed.value(
+            &[("chain_id", chain_id.as_str()), ("operator", *operator)],
+            *last_observed_event as f64,
+        )?;
+    }
+
+    let mut last_scraped = w.gauge_vec(
+        "minter_last_scraped_event",
+        "Latest event index scraped from the minter.",
+    )?;
+    for (chain_id, operator, _, last_scraped_event) in &minters {
+        last_scraped = last_scraped.value(
+            &[("chain_id", chain_id.as_str()), ("operator", *operator)],
+            *last_scraped_event as f64,
+        )?;
+    }
+
+    let mut scrape_lag = w.gauge_vec(
+        "minter_scrape_lag",
+        "Number of observed minter events that are not scraped yet.",
+    )?;
+    for (chain_id, operator, last_observed_event, last_scraped_event) in &minters {
+        scrape_lag = scrape_lag.value(
+            &[("chain_id", chain_id.as_str()), ("operator", *operator)],
+            last_observed_event.saturating_sub(*last_scraped_event) as f64,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Statuses are read from counters kept by the state, so a scrape does not walk every transaction
+fn encode_transaction_metrics(w: &mut MetricsEncoder<Vec<u8>>, s: &State) -> std::io::Result<()> {
+    let status_counts: Vec<(TimelineStatus, u64)> = s.transaction_status_counts.iter().collect();
+
+    w.encode_gauge(
+        "evm_to_icp_transactions",
+        s.evm_to_icp_txs.len() as f64,
+        "Number of recorded evm to icp transactions.",
+    )?;
+
+    let mut evm_to_icp = w.gauge_vec(
+        "evm_to_icp_transactions_by_status",
+        "Number of recorded evm to icp transactions per status.",
+    )?;
+    for (status, count) in &status_counts {
+        if let TimelineStatus::EvmToIcp(status) = status {
+            evm_to_icp = evm_to_icp.value(
+                &[("status", evm_to_icp_status_label(status))],
+                *count as f64,
+            )?;
+        }
+    }
+
+    w.encode_gauge(
+        "icp_to_evm_transactions",
+        s.icp_to_evm_txs.len() as f64,
+        "Number of recorded icp to evm transactions.",
+    )?;
+
+    let mut icp_to_evm = w.gauge_vec(
+        "icp_to_evm_transactions_by_status",
+        "Number of recorded icp to evm transactions per status.",
+    )?;
+    for (status, count) in &status_counts {
+        if let TimelineStatus::IcpToEvm(status) = status {
+            icp_to_evm = icp_to_evm.value(
+                &[("status", icp_to_evm_status_label(status))],
+                *count as f64,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_token_metrics(w: &mut MetricsEncoder<Vec<u8>>, s: &State) -> std::io::Result<()> {
+    w.encode_gauge(
+        "icp_tokens",
+        s.icp_token_list.len() as f64,
+        "Number of icp tokens in the token list.",
+    )?;
+
+    w.encode_gauge(
+        "evm_tokens",
+        s.evm_token_list.len() as f64,
+        "Number of evm tokens in the token list.",
+    )?;
+
+    w.gauge_vec(
+        "bridge_pairs",
+        "Number of supported bridge pairs per operator.",
+    )?
+    .value(
+        &[("operator", operator_label(Operator::DfinityCkEthMinter))],
+        s.supported_ckerc20_tokens.len() as f64,
+    )?
+    .value(
+        &[("operator", operator_label(Operator::AppicMinter))],
+        s.supported_twin_appic_tokens.len() as f64,
+    )?;
+
+    Ok(())
+}
+
+fn operator_label(operator: Operator) -> &'static str {
+    match operator {
+        Operator::DfinityCkEthMinter => "dfinity_cketh_minter",
+        Operator::AppicMinter => "appic_minter",
+    }
+}
+
+fn evm_to_icp_status_label(status: &EvmToIcpStatus) -> &'static str {
+    match status {
+        EvmToIcpStatus::PendingVerification => "pending_verification",
+        EvmToIcpStatus::Accepted => "accepted",
+        EvmToIcpStatus::Minted => "minted",
+        EvmToIcpStatus::Invalid(_) => "invalid",
+        EvmToIcpStatus::Quarantined => "quarantined",
+    }
+}
+
+fn icp_to_evm_status_label(status: &IcpToEvmStatus) -> &'static str {
+    match status {
+        IcpToEvmStatus::PendingVerification => "pending_verification",
+        IcpToEvmStatus::Accepted => "accepted",
+        IcpToEvmStatus::Created => "created",
+        IcpToEvmStatus::SignedTransaction => "signed_transaction",
+        IcpToEvmStatus::FinalizedTransaction => "finalized_transaction",
+        IcpToEvmStatus::ReplacedTransaction => "replaced_transaction",
+        IcpToEvmStatus::Reimbursed => "reimbursed",
+        IcpToEvmStatus::QuarantinedReimbursement => "quarantined_reimbursement",
+        IcpToEvmStatus::Successful => "successful",
+        IcpToEvmStatus::Failed => "failed",
+    }
+}
+
+// Returns the amount of heap memory in bytes that has been allocated
+#[cfg(target_arch = "wasm32")]
+fn heap_memory_size_bytes() -> usize {
+    core::arch::wasm32::memory_size(0) * WASM_PAGE_SIZE_IN_BYTES as usize
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn heap_memory_size_bytes() -> usize {
+    0
+}
@@ -218,6 +218,13 @@ pub mod events {
         SyncedToBlock {
             block_number: Nat,
         },
+        // Only converted from dfinity minter events, which scrape each log type separately
+        SyncedErc20ToBlock {
+            block_number: Nat,
+        },
+        SyncedDepositWithSubaccountToBlock {
+            block_number: Nat,
+        },
 
         AcceptedNativeWithdrawalRequest {
             withdrawal_amount: Nat,
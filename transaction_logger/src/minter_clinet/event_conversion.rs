@@ -1,6 +1,7 @@
 use crate::minter_clinet::appic_minter_types::events::Event as AppicEvent;
 
 use crate::minter_clinet::appic_minter_types::events::EventPayload as AppicEventPayload;
+use crate::minter_clinet::dfinity_ck_minter_types::events::Event as DfinityEvent;
 use crate::minter_clinet::dfinity_ck_minter_types::events::EventPayload as DfinityEventPayload;
 
 use crate::minter_clinet::{AppicGetEventsResult, DfinityCkGetEventsResult};
@@ -10,274 +11,77 @@ use super::appic_minter_types::events::EventSource as AppicEventSource;
 // standard type for events returned from minters
 #[derive(PartialEq, PartialOrd, Ord, Eq)]
 pub struct Events {
-    pub events: Vec<AppicEvent>,
+    // Every event is paired with its index on the minter
+    pub events: Vec<(u64, AppicEvent)>,
 }
 
 // A trait for filtering and mapping EventResults form both appic and dfinity cketh minters into a Standard Event type
+// start is the index of the first event in the response
 pub trait Reduce {
-    fn reduce(self) -> Events;
+    fn reduce(self, start: u64) -> Events;
 }
 
 impl Reduce for DfinityCkGetEventsResult {
-    fn reduce(self) -> Events {
-        Events {
-            events: AppicGetEventsResult::from(self).events,
-        }
+    fn reduce(self, start: u64) -> Events {
+        // Indexes are assigned before the conversion drops events, so they match the minter
+        let events = self
+            .events
+            .into_iter()
+            .zip(start..)
+            .filter_map(|(event, index)| convert_dfinity_event(event).map(|event| (index, event)))
+            .collect();
+
+        Events { events }
     }
 }
 
 impl Reduce for AppicGetEventsResult {
-    fn reduce(self) -> Events {
-        let reduced: Vec<AppicEvent> = self
+    fn reduce(self, start: u64) -> Events {
+        let events = self
             .events
             .into_iter()
-            .filter(|event| {
-                matches!(
-                    event.payload,
-                    AppicEventPayload::AcceptedDeposit { .. }
-                        | AppicEventPayload::AcceptedErc20Deposit { .. }
-                        | AppicEventPayload::MintedNative { .. }
-                        | AppicEventPayload::MintedErc20 { .. }
-                        | AppicEventPayload::AcceptedNativeWithdrawalRequest { .. }
-                        | AppicEventPayload::CreatedTransaction { .. }
-                        | AppicEventPayload::SignedTransaction { .. }
-                        | AppicEventPayload::ReplacedTransaction { .. }
-                        | AppicEventPayload::FinalizedTransaction { .. }
-                        | AppicEventPayload::ReimbursedNativeWithdrawal { .. }
-                        | AppicEventPayload::ReimbursedErc20Withdrawal { .. }
-                        | AppicEventPayload::AcceptedErc20WithdrawalRequest { .. }
-                        | AppicEventPayload::FailedErc20WithdrawalRequest { .. }
-                        | AppicEventPayload::InvalidDeposit { .. }
-                        | AppicEventPayload::QuarantinedDeposit { .. }
-                        | AppicEventPayload::QuarantinedReimbursement { .. }
-                )
-            })
+            .zip(start..)
+            .map(|(event, index)| (index, event))
+            .filter(|(_index, event)| is_scraped_payload(&event.payload))
             .collect();
-        Events { events: reduced }
+
+        Events { events }
     }
 }
 
+fn is_scraped_payload(payload: &AppicEventPayload) -> bool {
+    matches!(
+        payload,
+        AppicEventPayload::AcceptedDeposit { .. }
+            | AppicEventPayload::AcceptedErc20Deposit { .. }
+            | AppicEventPayload::MintedNative { .. }
+            | AppicEventPayload::MintedErc20 { .. }
+            | AppicEventPayload::AcceptedNativeWithdrawalRequest { .. }
+            | AppicEventPayload::CreatedTransaction { .. }
+            | AppicEventPayload::SignedTransaction { .. }
+            | AppicEventPayload::ReplacedTransaction { .. }
+            | AppicEventPayload::FinalizedTransaction { .. }
+            | AppicEventPayload::ReimbursedNativeWithdrawal { .. }
+            | AppicEventPayload::ReimbursedErc20Withdrawal { .. }
+            | AppicEventPayload::AcceptedErc20WithdrawalRequest { .. }
+            | AppicEventPayload::FailedErc20WithdrawalRequest { .. }
+            | AppicEventPayload::InvalidDeposit { .. }
+            | AppicEventPayload::QuarantinedDeposit { .. }
+            | AppicEventPayload::QuarantinedReimbursement { .. }
+            | AppicEventPayload::SyncedToBlock { .. }
+            | AppicEventPayload::SyncedErc20ToBlock { .. }
+            | AppicEventPayload::SyncedDepositWithSubaccountToBlock { .. }
+            | AppicEventPayload::SkippedBlock { .. }
+            | AppicEventPayload::AddedErc20Token { .. }
+    )
+}
+
 impl From<DfinityCkGetEventsResult> for AppicGetEventsResult {
     fn from(value: DfinityCkGetEventsResult) -> AppicGetEventsResult {
         let filtered_mapped: Vec<AppicEvent> = value
             .events
             .into_iter()
-            .filter_map(|event| {
-                let timestamp = event.timestamp;
-
-                let event_payload = match event.payload {
-                    DfinityEventPayload::Init(..)
-                    | DfinityEventPayload::Upgrade(..)
-                    | DfinityEventPayload::SyncedToBlock { .. }
-                    | DfinityEventPayload::SyncedErc20ToBlock { .. }
-                    | DfinityEventPayload::SyncedDepositWithSubaccountToBlock { .. }
-                    | DfinityEventPayload::SkippedBlock { .. }
-                    | DfinityEventPayload::AddedCkErc20Token { .. } => None,
-
-                    DfinityEventPayload::AcceptedDeposit {
-                        transaction_hash,
-                        block_number,
-                        log_index,
-                        from_address,
-                        value,
-                        principal,
-                        subaccount,
-                    } => Some(AppicEventPayload::AcceptedDeposit {
-                        transaction_hash,
-                        block_number,
-                        log_index,
-                        from_address,
-                        value,
-                        principal,
-                        subaccount,
-                    }),
-
-                    DfinityEventPayload::AcceptedErc20Deposit {
-                        transaction_hash,
-                        block_number,
-                        log_index,
-                        from_address,
-                        value,
-                        principal,
-                        erc20_contract_address,
-                        subaccount,
-                    } => Some(AppicEventPayload::AcceptedErc20Deposit {
-                        transaction_hash,
-                        block_number,
-                        log_index,
-                        from_address,
-                        value,
-                        principal,
-                        erc20_contract_address,
-                        subaccount,
-                    }),
-
-                    DfinityEventPayload::InvalidDeposit {
-                        event_source,
-                        reason,
-                    } => Some(AppicEventPayload::InvalidDeposit {
-                        event_source: AppicEventSource {
-                            log_index: event_source.log_index,
-                            transaction_hash: event_source.transaction_hash,
-                        },
-                        reason,
-                    }),
-
-                    DfinityEventPayload::MintedCkEth {
-                        event_source,
-                        mint_block_index,
-                    } => Some(AppicEventPayload::MintedNative {
-                        event_source: AppicEventSource {
-                            log_index: event_source.log_index,
-                            transaction_hash: event_source.transaction_hash,
-                        },
-                        mint_block_index,
-                    }),
-
-                    DfinityEventPayload::AcceptedEthWithdrawalRequest {
-                        withdrawal_amount,
-                        destination,
-                        ledger_burn_index,
-                        from,
-                        from_subaccount,
-                        created_at,
-                    } => Some(AppicEventPayload::AcceptedNativeWithdrawalRequest {
-                        withdrawal_amount,
-                        destination,
-                        ledger_burn_index,
-                        from,
-                        from_subaccount,
-                        created_at,
-                    }),
-
-                    DfinityEventPayload::CreatedTransaction {
-                        withdrawal_id,
-                        transaction,
-                    } => Some(AppicEventPayload::CreatedTransaction {
-                        withdrawal_id,
-                        transaction: transaction.into(),
-                    }),
-
-                    DfinityEventPayload::SignedTransaction {
-                        withdrawal_id,
-                        raw_transaction,
-                    } => Some(AppicEventPayload::SignedTransaction {
-                        withdrawal_id,
-                        raw_transaction,
-                    }),
-
-                    DfinityEventPayload::ReplacedTransaction {
-                        withdrawal_id,
-                        transaction,
-                    } => Some(AppicEventPayload::ReplacedTransaction {
-                        withdrawal_id,
-                        transaction: transaction.into(),
-                    }),
-
-                    DfinityEventPayload::FinalizedTransaction {
-                        withdrawal_id,
-                        transaction_receipt,
-                    } => Some(AppicEventPayload::FinalizedTransaction {
-                        withdrawal_id,
-                        transaction_receipt: transaction_receipt.into(),
-                    }),
-
-                    DfinityEventPayload::ReimbursedEthWithdrawal {
-                        reimbursed_in_block,
-                        withdrawal_id,
-                        reimbursed_amount,
-                        transaction_hash,
-                    } => Some(AppicEventPayload::ReimbursedNativeWithdrawal {
-                        reimbursed_in_block,
-                        withdrawal_id,
-                        reimbursed_amount,
-                        transaction_hash,
-                    }),
-
-                    DfinityEventPayload::ReimbursedErc20Withdrawal {
-                        withdrawal_id,
-                        burn_in_block,
-                        reimbursed_in_block,
-                        ledger_id,
-                        reimbursed_amount,
-                        transaction_hash,
-                    } => Some(AppicEventPayload::ReimbursedErc20Withdrawal {
-                        withdrawal_id,
-                        burn_in_block,
-                        reimbursed_in_block,
-                        ledger_id,
-                        reimbursed_amount,
-                        transaction_hash,
-                    }),
-
-                    DfinityEventPayload::AcceptedErc20WithdrawalRequest {
-                        max_transaction_fee,
-                        withdrawal_amount,
-                        erc20_contract_address,
-                        destination,
-                        cketh_ledger_burn_index,
-                        ckerc20_ledger_id,
-                        ckerc20_ledger_burn_index,
-                        from,
-                        from_subaccount,
-                        created_at,
-                    } => Some(AppicEventPayload::AcceptedErc20WithdrawalRequest {
-                        max_transaction_fee,
-                        withdrawal_amount,
-                        erc20_contract_address,
-                        destination,
-                        native_ledger_burn_index: cketh_ledger_burn_index,
-                        erc20_ledger_id: ckerc20_ledger_id,
-                        erc20_ledger_burn_index: ckerc20_ledger_burn_index,
-                        from,
-                        from_subaccount,
-                        created_at,
-                    }),
-
-                    DfinityEventPayload::MintedCkErc20 {
-                        event_source,
-                        mint_block_index,
-                        ckerc20_token_symbol,
-                        erc20_contract_address,
-                    } => Some(AppicEventPayload::MintedErc20 {
-                        event_source: AppicEventSource {
-                            log_index: event_source.log_index,
-                            transaction_hash: event_source.transaction_hash,
-                        },
-                        mint_block_index,
-                        erc20_token_symbol: ckerc20_token_symbol,
-                        erc20_contract_address,
-                    }),
-
-                    DfinityEventPayload::QuarantinedDeposit { event_source } => {
-                        Some(AppicEventPayload::QuarantinedDeposit {
-                            event_source: AppicEventSource {
-                                log_index: event_source.log_index,
-                                transaction_hash: event_source.transaction_hash,
-                            },
-                        })
-                    }
-
-                    DfinityEventPayload::QuarantinedReimbursement { index } => {
-                        Some(AppicEventPayload::QuarantinedReimbursement {
-                            index: index.into(),
-                        })
-                    }
-                    DfinityEventPayload::FailedErc20WithdrawalRequest {
-                        withdrawal_id,
-                        reimbursed_amount,
-                        to,
-                        to_subaccount,
-                    } => Some(AppicEventPayload::FailedErc20WithdrawalRequest {
-                        withdrawal_id,
-                        reimbursed_amount,
-                        to,
-                        to_subaccount,
-                    }),
-                };
-
-                event_payload.map(|payload| AppicEvent { timestamp, payload })
-            })
+            .filter_map(convert_dfinity_event)
             .collect();
 
         AppicGetEventsResult {
@@ -286,3 +90,243 @@ impl From<DfinityCkGetEventsResult> for AppicGetEventsResult {
         }
     }
 }
+
+// Maps a dfinity event into the appic one, events that are not scraped are dropped
+fn convert_dfinity_event(event: DfinityEvent) -> Option<AppicEvent> {
+    let timestamp = event.timestamp;
+
+    let event_payload = match event.payload {
+        DfinityEventPayload::Init(..) | DfinityEventPayload::Upgrade(..) => None,
+
+        DfinityEventPayload::AddedCkErc20Token {
+            chain_id,
+            address,
+            ckerc20_token_symbol,
+            ckerc20_ledger_id,
+        } => Some(AppicEventPayload::AddedErc20Token {
+            chain_id,
+            address,
+            erc20_token_symbol: ckerc20_token_symbol,
+            erc20_ledger_id: ckerc20_ledger_id,
+        }),
+
+        DfinityEventPayload::SyncedToBlock { block_number } => {
+            Some(AppicEventPayload::SyncedToBlock { block_number })
+        }
+
+        DfinityEventPayload::SyncedErc20ToBlock { block_number } => {
+            Some(AppicEventPayload::SyncedErc20ToBlock { block_number })
+        }
+
+        DfinityEventPayload::SyncedDepositWithSubaccountToBlock { block_number } => {
+            Some(AppicEventPayload::SyncedDepositWithSubaccountToBlock { block_number })
+        }
+
+        DfinityEventPayload::SkippedBlock { block_number, .. } => {
+            Some(AppicEventPayload::SkippedBlock { block_number })
+        }
+
+        DfinityEventPayload::AcceptedDeposit {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            subaccount,
+        } => Some(AppicEventPayload::AcceptedDeposit {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            subaccount,
+        }),
+
+        DfinityEventPayload::AcceptedErc20Deposit {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+            subaccount,
+        } => Some(AppicEventPayload::AcceptedErc20Deposit {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+            subaccount,
+        }),
+
+        DfinityEventPayload::InvalidDeposit {
+            event_source,
+            reason,
+        } => Some(AppicEventPayload::InvalidDeposit {
+            event_source: AppicEventSource {
+                log_index: event_source.log_index,
+                transaction_hash: event_source.transaction_hash,
+            },
+            reason,
+        }),
+
+        DfinityEventPayload::MintedCkEth {
+            event_source,
+            mint_block_index,
+        } => Some(AppicEventPayload::MintedNative {
+            event_source: AppicEventSource {
+                log_index: event_source.log_index,
+                transaction_hash: event_source.transaction_hash,
+            },
+            mint_block_index,
+        }),
+
+        DfinityEventPayload::AcceptedEthWithdrawalRequest {
+            withdrawal_amount,
+            destination,
+            ledger_burn_index,
+            from,
+            from_subaccount,
+            created_at,
+        } => Some(AppicEventPayload::AcceptedNativeWithdrawalRequest {
+            withdrawal_amount,
+            destination,
+            ledger_burn_index,
+            from,
+            from_subaccount,
+            created_at,
+        }),
+
+        DfinityEventPayload::CreatedTransaction {
+            withdrawal_id,
+            transaction,
+        } => Some(AppicEventPayload::CreatedTransaction {
+            withdrawal_id,
+            transaction: transaction.into(),
+        }),
+
+        DfinityEventPayload::SignedTransaction {
+            withdrawal_id,
+            raw_transaction,
+        } => Some(AppicEventPayload::SignedTransaction {
+            withdrawal_id,
+            raw_transaction,
+        }),
+
+        DfinityEventPayload::ReplacedTransaction {
+            withdrawal_id,
+            transaction,
+        } => Some(AppicEventPayload::ReplacedTransaction {
+            withdrawal_id,
+            transaction: transaction.into(),
+        }),
+
+        DfinityEventPayload::FinalizedTransaction {
+            withdrawal_id,
+            transaction_receipt,
+        } => Some(AppicEventPayload::FinalizedTransaction {
+            withdrawal_id,
+            transaction_receipt: transaction_receipt.into(),
+        }),
+
+        DfinityEventPayload::ReimbursedEthWithdrawal {
+            reimbursed_in_block,
+            withdrawal_id,
+            reimbursed_amount,
+            transaction_hash,
+        } => Some(AppicEventPayload::ReimbursedNativeWithdrawal {
+            reimbursed_in_block,
+            withdrawal_id,
+            reimbursed_amount,
+            transaction_hash,
+        }),
+
+        DfinityEventPayload::ReimbursedErc20Withdrawal {
+            withdrawal_id,
+            burn_in_block,
+            reimbursed_in_block,
+            ledger_id,
+            reimbursed_amount,
+            transaction_hash,
+        } => Some(AppicEventPayload::ReimbursedErc20Withdrawal {
+            withdrawal_id,
+            burn_in_block,
+            reimbursed_in_block,
+            ledger_id,
+            reimbursed_amount,
+            transaction_hash,
+        }),
+
+        DfinityEventPayload::AcceptedErc20WithdrawalRequest {
+            max_transaction_fee,
+            withdrawal_amount,
+            erc20_contract_address,
+            destination,
+            cketh_ledger_burn_index,
+            ckerc20_ledger_id,
+            ckerc20_ledger_burn_index,
+            from,
+            from_subaccount,
+            created_at,
+        } => Some(AppicEventPayload::AcceptedErc20WithdrawalRequest {
+            max_transaction_fee,
+            withdrawal_amount,
+            erc20_contract_address,
+            destination,
+            native_ledger_burn_index: cketh_ledger_burn_index,
+            erc20_ledger_id: ckerc20_ledger_id,
+            erc20_ledger_burn_index: ckerc20_ledger_burn_index,
+            from,
+            from_subaccount,
+            created_at,
+        }),
+
+        DfinityEventPayload::MintedCkErc20 {
+            event_source,
+            mint_block_index,
+            ckerc20_token_symbol,
+            erc20_contract_address,
+        } => Some(AppicEventPayload::MintedErc20 {
+            event_source: AppicEventSource {
+                log_index: event_source.log_index,
+                transaction_hash: event_source.transaction_hash,
+            },
+            mint_block_index,
+            erc20_token_symbol: ckerc20_token_symbol,
+            erc20_contract_address,
+        }),
+
+        DfinityEventPayload::QuarantinedDeposit { event_source } => {
+            Some(AppicEventPayload::QuarantinedDeposit {
+                event_source: AppicEventSource {
+                    log_index: event_source.log_index,
+                    transaction_hash: event_source.transaction_hash,
+                },
+            })
+        }
+
+        DfinityEventPayload::QuarantinedReimbursement { index } => {
+            Some(AppicEventPayload::QuarantinedReimbursement {
+                index: index.into(),
+            })
+        }
+        DfinityEventPayload::FailedErc20WithdrawalRequest {
+            withdrawal_id,
+            reimbursed_amount,
+            to,
+            to_subaccount,
+        } => Some(AppicEventPayload::FailedErc20WithdrawalRequest {
+            withdrawal_id,
+            reimbursed_amount,
+            to,
+            to_subaccount,
+        }),
+    };
+
+    event_payload.map(|payload| AppicEvent { timestamp, payload })
+}
@@ -0,0 +1,129 @@
+// Runtime replaying canned responses, used to test the inter canister calls
+// of the scraping and token services without a replica
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use candid::{CandidType, Principal};
+use serde::de::DeserializeOwned;
+
+use super::{CallError, Reason, Runtime};
+
+type CannedResponse = Result<Vec<u8>, CallError>;
+
+#[derive(Clone, Default)]
+pub struct MockRuntime {
+    responses: Arc<Mutex<BTreeMap<(Principal, String), VecDeque<CannedResponse>>>>,
+    calls: Arc<Mutex<Vec<(Principal, String)>>>,
+    time: Arc<Mutex<u64>>,
+}
+
+impl MockRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Queues a response for the next call to `method` on `canister_id`,
+    // responses to the same method are replayed in the order they were added
+    pub fn add_response<T: CandidType>(&self, canister_id: Principal, method: &str, response: T) {
+        let encoded = candid::encode_one(response).expect("BUG: failed to encode canned response");
+        self.push(canister_id, method, Ok(encoded));
+    }
+
+    pub fn add_error(&self, canister_id: Principal, method: &str, reason: Reason) {
+        self.push(
+            canister_id,
+            method,
+            Err(CallError {
+                method: method.to_string(),
+                reason,
+            }),
+        );
+    }
+
+    pub fn set_time(&self, time: u64) {
+        *self.time.lock().unwrap() = time;
+    }
+
+    // All calls made so far in the order they were made
+    pub fn calls(&self) -> Vec<(Principal, String)> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    // Number of responses that were queued but never consumed
+    pub fn pending_responses(&self) -> usize {
+        self.responses
+            .lock()
+            .unwrap()
+            .values()
+            .map(VecDeque::len)
+            .sum()
+    }
+
+    fn push(&self, canister_id: Principal, method: &str, response: CannedResponse) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry((canister_id, method.to_string()))
+            .or_default()
+            .push_back(response);
+    }
+}
+
+#[async_trait]
+impl Runtime for MockRuntime {
+    async fn call_canister<I, O>(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        _args: I,
+    ) -> Result<O, CallError>
+    where
+        I: CandidType + Debug + Send + 'static,
+        O: CandidType + DeserializeOwned + Debug + 'static,
+    {
+        self.calls
+            .lock()
+            .unwrap()
+            .push((canister_id, method.to_string()));
+
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .get_mut(&(canister_id, method.to_string()))
+            .and_then(VecDeque::pop_front)
+            .unwrap_or_else(|| {
+                Err(CallError {
+                    method: method.to_string(),
+                    reason: Reason::Rejected(format!(
+                        "no canned response for {} on {}",
+                        method, canister_id
+                    )),
+                })
+            })?;
+
+        Ok(candid::decode_one(&response).expect("BUG: canned response has the wrong type"))
+    }
+
+    // Cycles are not accounted for, the call is replayed like any other
+    async fn call_canister_with_cycles<I, O>(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        args: I,
+        _cycles: u128,
+    ) -> Result<O, CallError>
+    where
+        I: CandidType + Debug + Send + 'static,
+        O: CandidType + DeserializeOwned + Debug + 'static,
+    {
+        self.call_canister(canister_id, method, args).await
+    }
+
+    fn time(&self) -> u64 {
+        *self.time.lock().unwrap()
+    }
+}
@@ -1,6 +1,8 @@
 pub mod appic_minter_types;
 pub mod dfinity_ck_minter_types;
 pub mod event_conversion;
+#[cfg(test)]
+pub mod mock_runtime;
 use async_trait::async_trait;
 use candid::Principal;
 
@@ -36,6 +38,21 @@ pub trait Runtime {
     where
         I: CandidType + Debug + Send + 'static,
         O: CandidType + DeserializeOwned + Debug + 'static;
+
+    // Making inter canister calls that attach cycles
+    async fn call_canister_with_cycles<I, O>(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        args: I,
+        cycles: u128,
+    ) -> Result<O, CallError>
+    where
+        I: CandidType + Debug + Send + 'static,
+        O: CandidType + DeserializeOwned + Debug + 'static;
+
+    // Current time in nanoseconds since the unix epoch
+    fn time(&self) -> u64;
 }
 
 #[derive(Copy, Clone)]
@@ -63,68 +80,94 @@ impl Runtime for IcRunTime {
             }),
         }
     }
+
+    async fn call_canister_with_cycles<I, O>(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        args: I,
+        cycles: u128,
+    ) -> Result<O, CallError>
+    where
+        I: CandidType + Debug + Send + 'static,
+        O: CandidType + DeserializeOwned + Debug + 'static,
+    {
+        let res: Result<(O,), _> =
+            ic_cdk::api::call::call_with_payment128(canister_id, method, (&args,), cycles).await;
+
+        match res {
+            Ok((output,)) => Ok(output),
+            Err((code, msg)) => Err(CallError {
+                method: method.to_string(),
+                reason: Reason::from_reject(code, msg),
+            }),
+        }
+    }
+
+    fn time(&self) -> u64 {
+        ic_cdk::api::time()
+    }
 }
 
-pub struct MinterClient {
-    runtime: IcRunTime,
+pub struct MinterClient<R: Runtime = IcRunTime> {
+    runtime: R,
     minter_id: Principal,
     operator: Operator,
 }
 
 impl From<&Minter> for MinterClient {
     fn from(value: &Minter) -> Self {
-        Self {
-            runtime: IcRunTime(),
-            minter_id: value.id,
-            operator: value.operator,
-        }
+        Self::new(value.id, value.operator)
     }
 }
 
 impl MinterClient {
     pub fn new(minter_id: Principal, operator: Operator) -> Self {
+        Self::with_runtime(IcRunTime(), minter_id, operator)
+    }
+}
+
+impl<R: Runtime> MinterClient<R> {
+    pub fn with_runtime(runtime: R, minter_id: Principal, operator: Operator) -> Self {
         Self {
-            runtime: IcRunTime(),
+            runtime,
             minter_id,
             operator,
         }
     }
 
-    // Get total evetns count
-    pub async fn get_total_events_count(&self) -> u64 {
-        // Get total events count
-        let toatl_events_count = match self.operator {
-            Operator::DfinityCkEthMinter => {
-                self.runtime
-                    .call_canister::<DfinityCkGetEventsArg, DfinityCkGetEventsResult>(
-                        self.minter_id,
-                        "get_events",
-                        DfinityCkGetEventsArg {
-                            start: 0,
-                            length: 0,
-                        },
-                    )
-                    .await
-                    .expect("Call should not fail. will retry in next interval")
-                    .total_event_count
-            }
-            Operator::AppicMinter => {
-                self.runtime
-                    .call_canister::<AppicGetEventsArg, AppicGetEventsResult>(
-                        self.minter_id,
-                        "get_events",
-                        AppicGetEventsArg {
-                            start: 0,
-                            length: 0,
-                        },
-                    )
-                    .await
-                    .expect("Call should not fail. will retry in next interval")
-                    .total_event_count
-            }
-        };
+    pub fn runtime(&self) -> &R {
+        &self.runtime
+    }
 
-        toatl_events_count
+    // Get total evetns count
+    pub async fn get_total_events_count(&self) -> Result<u64, CallError> {
+        match self.operator {
+            Operator::DfinityCkEthMinter => self
+                .runtime
+                .call_canister::<DfinityCkGetEventsArg, DfinityCkGetEventsResult>(
+                    self.minter_id,
+                    "get_events",
+                    DfinityCkGetEventsArg {
+                        start: 0,
+                        length: 0,
+                    },
+                )
+                .await
+                .map(|response| response.total_event_count),
+            Operator::AppicMinter => self
+                .runtime
+                .call_canister::<AppicGetEventsArg, AppicGetEventsResult>(
+                    self.minter_id,
+                    "get_events",
+                    AppicGetEventsArg {
+                        start: 0,
+                        length: 0,
+                    },
+                )
+                .await
+                .map(|response| response.total_event_count),
+        }
     }
 
     // scrape events
@@ -141,7 +184,7 @@ impl MinterClient {
                     },
                 )
                 .await
-                .map(|response| response.reduce()),
+                .map(|response| response.reduce(from_event)),
             Operator::AppicMinter => self
                 .runtime
                 .call_canister::<AppicGetEventsArg, AppicGetEventsResult>(
@@ -153,7 +196,7 @@ impl MinterClient {
                     },
                 )
                 .await
-                .map(|response| response.reduce()),
+                .map(|response| response.reduce(from_event)),
         }
     }
 }
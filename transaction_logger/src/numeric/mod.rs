@@ -22,3 +22,26 @@ pub type LedgerBurnIndex = Id<BurnIndexTag, u64>;
 
 pub enum MintIndexTag {}
 pub type LedgerMintIndex = Id<BurnIndexTag, u64>;
+
+/// Fractional digits of usd prices and values.
+pub const USD_DECIMALS: u8 = 18;
+
+/// Usd price of one whole token, in fixed point with `USD_DECIMALS` fractional digits.
+pub enum UsdPriceTag {}
+pub type UsdPrice = CheckedAmountOf<UsdPriceTag>;
+
+/// Usd value, in fixed point with `USD_DECIMALS` fractional digits.
+pub enum UsdValueTag {}
+pub type UsdValue = CheckedAmountOf<UsdValueTag>;
+
+impl UsdPrice {
+    /// Usd value of an amount in the smallest units of a token with `decimals` decimals,
+    /// rounded down.
+    pub fn checked_value_of(self, amount: Erc20TokenAmount, decimals: u8) -> Option<UsdValue> {
+        let unit = ethnum::u256::from(10_u8).checked_pow(decimals as u32)?;
+        amount
+            .change_units::<UsdValueTag>()
+            .checked_mul(self.change_units())?
+            .checked_div_floor(unit)
+    }
+}
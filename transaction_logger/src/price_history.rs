@@ -0,0 +1,65 @@
+// Usd price series of icp tokens
+// Prices are kept hourly for the last 30 days and daily for two years, a daily point holds the
+// last price seen on its day
+
+use candid::{Nat, Principal};
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::CandidPricePoint;
+use crate::numeric::{UsdPrice, USD_DECIMALS};
+
+pub(crate) const NANOS_PER_HOUR: u64 = 60 * 60 * 1_000_000_000;
+
+const NANOS_PER_DAY: u64 = 24 * NANOS_PER_HOUR;
+
+pub const HOURLY_PRICE_RETENTION: u64 = 30 * NANOS_PER_DAY;
+
+pub const DAILY_PRICE_RETENTION: u64 = 2 * 365 * NANOS_PER_DAY;
+
+// Ledger id and start of the hour or day of a price point, in nanoseconds since the unix epoch
+#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct TokenPriceKey(pub Principal, pub u64);
+
+impl TokenPriceKey {
+    pub fn hourly(ledger_id: Principal, timestamp: u64) -> Self {
+        Self(ledger_id, timestamp - timestamp % NANOS_PER_HOUR)
+    }
+
+    pub fn daily(ledger_id: Principal, timestamp: u64) -> Self {
+        Self(ledger_id, timestamp - timestamp % NANOS_PER_DAY)
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.1
+    }
+}
+
+impl From<(TokenPriceKey, UsdPrice)> for CandidPricePoint {
+    fn from((key, usd_price): (TokenPriceKey, UsdPrice)) -> Self {
+        Self {
+            timestamp: key.timestamp(),
+            usd_price: usd_price.to_decimal_string(USD_DECIMALS),
+            usd_price_e18: Nat::from(usd_price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_round_price_points_down_to_their_hour_and_day() {
+        let ledger_id = Principal::anonymous();
+        let timestamp = 3 * NANOS_PER_DAY + 5 * NANOS_PER_HOUR + 42;
+
+        assert_eq!(
+            TokenPriceKey::hourly(ledger_id, timestamp),
+            TokenPriceKey(ledger_id, 3 * NANOS_PER_DAY + 5 * NANOS_PER_HOUR)
+        );
+        assert_eq!(
+            TokenPriceKey::daily(ledger_id, timestamp),
+            TokenPriceKey(ledger_id, 3 * NANOS_PER_DAY)
+        );
+    }
+}
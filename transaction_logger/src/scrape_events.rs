@@ -1,72 +1,261 @@
+use std::{cell::RefCell, collections::BTreeMap, str::FromStr};
+
 use crate::{
-    guard::TimerGuard,
+    guard::{TaskType, TimerGuard},
     logs::{DEBUG, INFO},
-    minter_clinet::MinterClient,
-    numeric::Erc20TokenAmount,
+    minter_clinet::{CallError, IcRunTime, MinterClient, Runtime},
+    numeric::{Erc20TokenAmount, LedgerBurnIndex},
     state::{
-        mutate_state, nat_to_ledger_burn_index, nat_to_ledger_mint_index, read_state, ChainId,
-        EvmToIcpTxIdentifier, IcpToEvmIdentifier, MinterKey, Operator,
+        checked_nat_to_erc20_amount, mutate_state, nat_to_block_number, nat_to_erc20_amount,
+        nat_to_ledger_burn_index, nat_to_ledger_mint_index, read_state, BridgePair, ChainId,
+        Erc20Identifier, EvmToIcpStatus, EvmToIcpTxIdentifier, FailedScrapeRangeKey,
+        IcpToEvmIdentifier, IcpToEvmStatus, Minter, MinterKey, Operator, Reimbursement,
+        SyncedLogType, TimelineStatus, TransactionKey,
     },
 };
 
-use crate::minter_clinet::appic_minter_types::events::EventPayload as AppicEventPayload;
+use crate::minter_clinet::appic_minter_types::events::{
+    EventPayload as AppicEventPayload, TransactionStatus,
+};
+use candid::{Nat, Principal};
+use futures::future::join_all;
 use ic_canister_log::log;
+use ic_ethereum_types::Address;
 
 use crate::minter_clinet::event_conversion::Events;
 const MAX_EVENTS_PER_RESPONSE: u64 = 100;
 
+// Upper bound of the backoff applied to a failing minter, unless its interval is longer
+const MAX_SCRAPE_BACKOFF_SECONDS: u64 = 60 * 60;
+
 pub const NATIVE_ERC20_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
 
+thread_local! {
+    // Time in nanoseconds after which a minter is due for scraping again
+    // Kept on the heap, so every minter is scraped right after an upgrade
+    static NEXT_SCRAPE_TIMES: RefCell<BTreeMap<MinterKey, u64>> = RefCell::default();
+
+    // Twin tokens announced by minter events whose icp ledger is not listed yet, with their symbol
+    // and ledger id; their pairs are recorded once the fee of the ledger is known
+    // Kept on the heap, update_bridge_pairs records the pairs of tokens lost on an upgrade
+    static PENDING_TWIN_TOKENS: RefCell<BTreeMap<(Operator, Erc20Identifier), (String, Principal)>> =
+        RefCell::default();
+}
+
 pub async fn scrape_events() {
-    // Issue a timer gaurd
-    let _gaurd = match TimerGuard::new(crate::guard::TaskType::ScrapeEvents) {
+    scrape_events_with_runtime(IcRunTime()).await
+}
+
+// Scrapes every active minter whose next scrape time has passed
+// Minters are scraped concurrently and each one holds its own guard, so a slow or
+// failing minter only delays itself
+pub async fn scrape_events_with_runtime<R: Runtime + Clone>(runtime: R) {
+    // ScrapeEvents is held while the token lists are initialized, rounds only check it and do
+    // not hold it themselves, minters are excluded by their own guards
+    if TimerGuard::is_active(&TaskType::ScrapeEvents) {
+        return;
+    }
+
+    let now = runtime.time();
+    let due_minters: Vec<MinterKey> = read_state(|s| s.get_active_minters())
+        .into_iter()
+        .map(|(minter_key, _minter)| minter_key)
+        .filter(|minter_key| is_scrape_due(minter_key, now))
+        .collect();
+
+    join_all(
+        due_minters
+            .into_iter()
+            .map(|minter_key| scrape_minter(runtime.clone(), minter_key)),
+    )
+    .await;
+}
+
+async fn scrape_minter<R: Runtime>(runtime: R, minter_key: MinterKey) {
+    // A minter that is still being scraped from a previous round is skipped
+    let _gaurd = match TimerGuard::new(TaskType::ScrapeMinter(minter_key.clone())) {
         Ok(gaurd) => gaurd,
         Err(_) => return,
     };
 
-    let minters = read_state(|s| s.get_minters());
+    let Some(minter) = read_state(|s| s.minters.get(&minter_key)) else {
+        return;
+    };
 
-    for (minter_key, minter) in minters.iter() {
-        let minter_client = MinterClient::from(minter);
+    let minter_client = MinterClient::with_runtime(runtime, minter.id, minter.operator);
+    scrape_minter_events(&minter_client, &minter_key, &minter).await;
+    record_pending_twin_tokens(minter_client.runtime(), &minter_key).await;
 
-        // Get the latest event count to update last_observed_event;
-        // -1 since the starting index in 0 not 1
-        let latest_event_count = minter_client.get_total_events_count().await - 1;
+    schedule_next_scrape(&minter_key, minter_client.runtime().time());
+}
 
-        // Check if the previos last_observed_event is greater or equal to latest one;
-        // If yes there should be no scraping for events and last_observed_event should not be updated
-        if minter.last_observed_event >= latest_event_count {
-            break;
-        };
+async fn scrape_minter_events<R: Runtime>(
+    minter_client: &MinterClient<R>,
+    minter_key: &MinterKey,
+    minter: &Minter,
+) {
+    // Get the latest event count to update last_observed_event;
+    // -1 since the starting index in 0 not 1
+    let latest_event_count = match minter_client.get_total_events_count().await {
+        Ok(total_event_count) => total_event_count.saturating_sub(1),
+        Err(err) => {
+            log!(
+                DEBUG,
+                "[Scraping Events] Failed to get events count for minter {:?}: {}",
+                minter_key,
+                err
+            );
+            mutate_state(|s| {
+                s.record_scrape_failure(minter_key, err.to_string(), minter_client.runtime().time())
+            });
+            return;
+        }
+    };
 
-        // Updating last observed event count
+    // Check if all the events up to the latest one are already scraped;
+    // last_scraped_event can be behind last_observed_event after an admin reset
+    if minter.last_scraped_event >= latest_event_count {
+        mutate_state(|s| s.record_scrape_success(minter_key, minter_client.runtime().time()));
+        return;
+    };
+
+    // Updating last observed event count
+    if latest_event_count > minter.last_observed_event {
         mutate_state(|s| s.update_last_observed_event(minter_key, latest_event_count));
+    }
 
-        let last_scraped_event = minter.last_scraped_event;
+    // Events after a failed range are held back until the range is recovered by
+    // retry_failed_scrape_ranges, otherwise they would be applied before the events they depend on
+    if read_state(|s| s.has_unresolved_failed_scrape_range(minter_key, minter.last_scraped_event)) {
+        log!(
+            DEBUG,
+            "[Scraping Events] Waiting for the failed range after event {} of minter {:?} to be recovered",
+            minter.last_scraped_event,
+            minter_key
+        );
+        return;
+    }
 
-        // Scraping logs between specified ranges
-        // MAX_EVENT_RESPONSE= 100 so the log range should not be more than 100
-        // min((last_observed_evnet - last_scraped_event),100) will be the specified range
-        // If last_observed_evnet - last_scraped_event contains more than 100, the event scaping will be divided into multiple calls
+    // Scraping logs between specified ranges
+    // MAX_EVENT_RESPONSE= 100 so the log range should not be more than 100
+    // min((last_observed_evnet - last_scraped_event),100) will be the specified range
+    // If last_observed_evnet - last_scraped_event contains more than 100, the event scaping will be divided into multiple calls
+    scrape_events_range(
+        latest_event_count,
+        minter.last_scraped_event,
+        MAX_EVENTS_PER_RESPONSE,
+        minter_client,
+        minter_key,
+        minter.evm_to_icp_fee,
+        minter.icp_to_evm_fee,
+    )
+    .await
+}
 
-        scrape_events_range(
-            latest_event_count,
-            last_scraped_event,
-            MAX_EVENTS_PER_RESPONSE,
-            &minter_client,
-            minter_key,
-            minter.evm_to_icp_fee,
-            minter.icp_to_evm_fee,
-        )
-        .await
+// Queries the fee of the ledgers of twin tokens announced by the minter and records their pairs
+// Tokens whose fee can not be queried stay pending until the next round
+async fn record_pending_twin_tokens<R: Runtime>(runtime: &R, minter_key: &MinterKey) {
+    let pending: Vec<((Operator, Erc20Identifier), (String, Principal))> = PENDING_TWIN_TOKENS
+        .with(|tokens| {
+            tokens
+                .borrow()
+                .iter()
+                .filter(|((operator, identifier), _token)| {
+                    *operator == minter_key.operator()
+                        && identifier.chain_id() == minter_key.chain_id()
+                })
+                .map(|(key, token)| (key.clone(), token.clone()))
+                .collect()
+        });
+
+    for ((operator, identifier), (twin_symbol, ledger_id)) in pending {
+        let fee = match runtime
+            .call_canister::<(), Nat>(ledger_id, "icrc1_fee", ())
+            .await
+        {
+            Ok(fee) => fee,
+            Err(err) => {
+                log!(
+                    DEBUG,
+                    "[Scraping Events] Failed to get the fee of ledger {} of twin token {}: {}",
+                    ledger_id,
+                    twin_symbol,
+                    err
+                );
+                continue;
+            }
+        };
+
+        PENDING_TWIN_TOKENS
+            .with(|tokens| tokens.borrow_mut().remove(&(operator, identifier.clone())));
+        let bridge_pair = mutate_state(|s| {
+            s.record_added_twin_token(
+                operator,
+                identifier,
+                &twin_symbol,
+                ledger_id,
+                checked_nat_to_erc20_amount(fee),
+            )
+        });
+        if let Some(bridge_pair) = bridge_pair {
+            log_recorded_bridge_pair(&bridge_pair, minter_key);
+        }
     }
 }
 
-pub async fn scrape_events_range(
+fn log_recorded_bridge_pair(bridge_pair: &BridgePair, minter_key: &MinterKey) {
+    log!(
+        INFO,
+        "[Scrape Events] Recorded bridge pair {} <> {} announced by {:?}",
+        bridge_pair.evm_token.symbol,
+        bridge_pair.icp_token.symbol,
+        minter_key
+    );
+}
+
+fn is_scrape_due(minter_key: &MinterKey, now: u64) -> bool {
+    NEXT_SCRAPE_TIMES.with(|times| {
+        times
+            .borrow()
+            .get(minter_key)
+            .map_or(true, |next_scrape_time| *next_scrape_time <= now)
+    })
+}
+
+// Schedules the next round of a minter after its interval, or after a backoff if it is failing
+fn schedule_next_scrape(minter_key: &MinterKey, now: u64) {
+    let (interval_seconds, consecutive_failures) = read_state(|s| {
+        (
+            s.get_scrape_interval(minter_key),
+            s.get_scrape_health(minter_key).consecutive_failures,
+        )
+    });
+
+    let next_scrape_time = now.saturating_add(scrape_delay(interval_seconds, consecutive_failures));
+    NEXT_SCRAPE_TIMES.with(|times| {
+        times
+            .borrow_mut()
+            .insert(minter_key.clone(), next_scrape_time)
+    });
+}
+
+// Delay in nanoseconds before the next round: the interval doubled on every consecutive failure
+pub fn scrape_delay(interval_seconds: u64, consecutive_failures: u64) -> u64 {
+    const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+    let max_delay_seconds = interval_seconds.max(MAX_SCRAPE_BACKOFF_SECONDS);
+    let exponent = consecutive_failures.min(20) as u32;
+    interval_seconds
+        .saturating_mul(1 << exponent)
+        .min(max_delay_seconds)
+        .saturating_mul(NANOS_PER_SECOND)
+}
+
+pub async fn scrape_events_range<R: Runtime>(
     last_observed_event: u64,
     last_scraped_event: u64,
     max_event_scrap: u64,
-    minter_client: &MinterClient,
+    minter_client: &MinterClient<R>,
     minter_key: &MinterKey,
     evm_to_icp_fee: Erc20TokenAmount,
     icp_to_evm_fee: Erc20TokenAmount,
@@ -95,6 +284,7 @@ pub async fn scrape_events_range(
 
         let mut attempts = 0; // Initialize retry counter
         let mut success = false; // Track success status
+        let mut last_error = String::new();
 
         while attempts < MAX_RETRIES {
             let events_result = minter_client.scrape_events(start, 100).await;
@@ -107,12 +297,23 @@ pub async fn scrape_events_range(
                         evm_to_icp_fee,
                         icp_to_evm_fee,
                     );
-                    mutate_state(|s| s.update_last_scraped_event(&minter_key, chunk_end));
+                    mutate_state(|s| {
+                        s.update_last_scraped_event(&minter_key, chunk_end);
+                        s.record_scrape_success(minter_key, minter_client.runtime().time());
+                    });
                     success = true; // Mark as successful
                     break; // Exit retry loop
                 }
                 Err(err) => {
                     attempts += 1;
+                    last_error = err.to_string();
+                    mutate_state(|s| {
+                        s.record_scrape_failure(
+                            minter_key,
+                            err.to_string(),
+                            minter_client.runtime().time(),
+                        )
+                    });
                     log!(
                         DEBUG,
                         "[Scraping Events] Error scraping events from {} to {}: {:?}. Retrying... ({}/{})",
@@ -126,7 +327,7 @@ pub async fn scrape_events_range(
                     if attempts >= MAX_RETRIES {
                         log!(
                             DEBUG,
-                            "[Scraping Events] Failed to scrape events from {} to {} after {} retries. Skipping...",
+                            "[Scraping Events] Failed to scrape events from {} to {} after {} retries. Recording the range for a later retry...",
                             start,
                             chunk_end,
                             MAX_RETRIES
@@ -140,7 +341,18 @@ pub async fn scrape_events_range(
             // Move to the next range only if scraping was successful
             start = chunk_end + 1;
         } else {
-            // If scraping ultimately fails, break to prevent an infinite loop
+            // If scraping ultimately fails, the range is stored and retried separately with a backoff;
+            // last_scraped_event stays at start - 1 so no event after the range is applied before it
+            mutate_state(|s| {
+                s.record_failed_scrape_range(
+                    minter_key,
+                    start,
+                    chunk_end,
+                    last_error,
+                    minter_client.runtime().time(),
+                    failed_range_retry_delay,
+                )
+            });
             log!(
                 DEBUG,
                 "[Scraping Events] Aborting further scraping due to repeated failures."
@@ -150,6 +362,143 @@ pub async fn scrape_events_range(
     }
 }
 
+// Backoff before retrying a failed range: 1 minute doubled on every failed attempt, capped at 1 day
+pub fn failed_range_retry_delay(attempts: u32) -> u64 {
+    const BASE_DELAY_NANOS: u64 = 60 * 1_000_000_000;
+    const MAX_DELAY_NANOS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    let exponent = attempts.saturating_sub(1).min(20);
+    BASE_DELAY_NANOS
+        .saturating_mul(1 << exponent)
+        .min(MAX_DELAY_NANOS)
+}
+
+// Retries the ranges that scrape_events_range failed to scrape once their backoff has expired
+pub async fn retry_failed_scrape_ranges() {
+    retry_failed_scrape_ranges_with_runtime(IcRunTime()).await
+}
+
+pub async fn retry_failed_scrape_ranges_with_runtime<R: Runtime + Clone>(runtime: R) {
+    let _gaurd = match TimerGuard::new(TaskType::RetryFailedScrapeRanges) {
+        Ok(gaurd) => gaurd,
+        Err(_) => return,
+    };
+
+    let due_ranges = read_state(|s| s.get_due_failed_scrape_ranges(runtime.time()));
+
+    for (FailedScrapeRangeKey(minter_key, from_event), range) in due_ranges {
+        let Some(minter) = read_state(|s| s.minters.get(&minter_key)) else {
+            mutate_state(|s| s.remove_failed_scrape_range(&minter_key, from_event));
+            continue;
+        };
+
+        log!(
+            INFO,
+            "[Scraping Events] Retrying failed range from {} to {} minter {:?}, attempt {}",
+            from_event,
+            range.to_event,
+            minter_key,
+            range.attempts + 1
+        );
+
+        match rescan_events_range(
+            &MinterClient::with_runtime(runtime.clone(), minter.id, minter.operator),
+            &minter_key,
+            from_event,
+            range.to_event,
+            minter.evm_to_icp_fee,
+            minter.icp_to_evm_fee,
+        )
+        .await
+        {
+            Ok(()) => {
+                log!(
+                    INFO,
+                    "[Scraping Events] Recovered failed range from {} to {} minter {:?}",
+                    from_event,
+                    range.to_event,
+                    minter_key
+                );
+                // The live scraper stopped right before the range, so it continues after it
+                mutate_state(|s| {
+                    let stopped_before_range = s.minters.get(&minter_key).is_some_and(|minter| {
+                        minter.last_scraped_event.saturating_add(1) == from_event
+                    });
+                    if stopped_before_range {
+                        s.update_last_scraped_event(&minter_key, range.to_event);
+                    }
+                });
+            }
+            Err((_next_event, err)) => {
+                log!(
+                    DEBUG,
+                    "[Scraping Events] Failed to retry range from {} to {} minter {:?}: {}",
+                    from_event,
+                    range.to_event,
+                    minter_key,
+                    err
+                );
+                mutate_state(|s| {
+                    s.record_failed_scrape_range(
+                        &minter_key,
+                        from_event,
+                        range.to_event,
+                        err.to_string(),
+                        runtime.time(),
+                        failed_range_retry_delay,
+                    )
+                });
+            }
+        }
+    }
+}
+
+// Fetches and reapplies the events between from_event and to_event (inclusive)
+// State transitions are idempotent, so events that were already applied are not affected
+// last_scraped_event is left untouched so the live scraper keeps its progress
+// On failure returns the first event that was not reapplied together with the error
+pub async fn rescan_events_range<R: Runtime>(
+    minter_client: &MinterClient<R>,
+    minter_key: &MinterKey,
+    from_event: u64,
+    to_event: u64,
+    evm_to_icp_fee: Erc20TokenAmount,
+    icp_to_evm_fee: Erc20TokenAmount,
+) -> Result<(), (u64, CallError)> {
+    let mut start = from_event;
+
+    while start <= to_event {
+        let chunk_end = std::cmp::min(start + MAX_EVENTS_PER_RESPONSE - 1, to_event);
+        log!(
+            INFO,
+            "[Rescanning Events] Rescanning events from {} to {} minter {:?}",
+            start,
+            chunk_end,
+            minter_key
+        );
+
+        let events = minter_client
+            .scrape_events(start, chunk_end - start + 1)
+            .await
+            .map_err(|err| (start, err))?;
+
+        apply_state_transition(
+            events,
+            minter_key.operator(),
+            minter_key.chain_id(),
+            evm_to_icp_fee,
+            icp_to_evm_fee,
+        );
+
+        start = chunk_end + 1;
+    }
+
+    // Skipped ranges inside the rescanned one are no longer gaps
+    mutate_state(|s| s.remove_failed_scrape_ranges_within(minter_key, from_event, to_event));
+
+    Ok(())
+}
+
 fn apply_state_transition(
     events: Events,
     operator: Operator,
@@ -157,180 +506,1203 @@ fn apply_state_transition(
     evm_to_icp_fee: Erc20TokenAmount,
     icp_to_evm_fee: Erc20TokenAmount,
 ) {
-    for event in events.events.into_iter() {
+    let minter_key = MinterKey(chain_id, operator);
+
+    for (event_index, event) in events.events.into_iter() {
+        let timeline_entry = timeline_entry(&event.payload, chain_id);
+
         // Applying the state transition
-        mutate_state(|s| match event.payload {
-            AppicEventPayload::Init(_init_arg) => {}
-            AppicEventPayload::Upgrade(_upgrade_arg) => {}
-            AppicEventPayload::AcceptedDeposit {
-                transaction_hash,
-                block_number,
-                from_address,
-                value,
-                principal,
-                subaccount,
-                ..
-            } => s.record_accepted_evm_to_icp(
-                EvmToIcpTxIdentifier::new(&transaction_hash, chain_id),
-                transaction_hash,
-                block_number,
-                from_address,
-                value,
-                principal,
-                NATIVE_ERC20_ADDRESS.to_string(),
-                subaccount,
-                chain_id,
-                operator,
-                event.timestamp,
-            ),
-            AppicEventPayload::AcceptedErc20Deposit {
-                transaction_hash,
-                block_number,
-                log_index: _,
-                from_address,
-                value,
-                principal,
-                erc20_contract_address,
-                subaccount,
-            } => s.record_accepted_evm_to_icp(
-                EvmToIcpTxIdentifier::new(&transaction_hash, chain_id),
-                transaction_hash,
-                block_number,
-                from_address,
-                value,
-                principal,
-                erc20_contract_address,
-                subaccount,
-                chain_id,
-                operator,
-                event.timestamp,
-            ),
-            AppicEventPayload::InvalidDeposit {
-                event_source,
-                reason,
-            } => s.record_invalid_evm_to_icp(
-                EvmToIcpTxIdentifier::new(&event_source.transaction_hash, chain_id),
-                reason,
-            ),
-            AppicEventPayload::MintedNative {
-                event_source,
-                mint_block_index,
-            } => s.record_minted_evm_to_icp(
-                EvmToIcpTxIdentifier::new(&event_source.transaction_hash, chain_id),
-                evm_to_icp_fee,
-                nat_to_ledger_mint_index(&mint_block_index),
-            ),
-            AppicEventPayload::SyncedToBlock { .. } => {}
-            AppicEventPayload::AcceptedNativeWithdrawalRequest {
-                withdrawal_amount,
-                destination,
-                ledger_burn_index,
-                from,
-                from_subaccount,
-                created_at,
-            } => s.record_accepted_icp_to_evm(
-                IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&ledger_burn_index), chain_id),
-                None,
-                withdrawal_amount,
-                NATIVE_ERC20_ADDRESS.to_string(),
-                destination,
-                ledger_burn_index,
-                None,
-                from,
-                from_subaccount,
-                created_at,
-                operator,
-                chain_id,
-                event.timestamp,
-            ),
-            AppicEventPayload::CreatedTransaction { withdrawal_id, .. } => s
-                .record_created_icp_to_evm(IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&withdrawal_id),
+        mutate_state(|s| {
+            match event.payload {
+                AppicEventPayload::Init(_init_arg) => {}
+                AppicEventPayload::Upgrade(_upgrade_arg) => {}
+                AppicEventPayload::AcceptedDeposit {
+                    transaction_hash,
+                    block_number,
+                    from_address,
+                    value,
+                    principal,
+                    subaccount,
+                    ..
+                } => s.record_accepted_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&transaction_hash, chain_id),
+                    transaction_hash,
+                    block_number,
+                    from_address,
+                    value,
+                    principal,
+                    NATIVE_ERC20_ADDRESS.to_string(),
+                    subaccount,
                     chain_id,
-                )),
-            AppicEventPayload::SignedTransaction { withdrawal_id, .. } => s
-                .record_signed_icp_to_evm(IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&withdrawal_id),
-                    chain_id,
-                )),
-            AppicEventPayload::ReplacedTransaction { withdrawal_id, .. } => s
-                .record_replaced_icp_to_evm(IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&withdrawal_id),
+                    operator,
+                    event.timestamp,
+                ),
+                AppicEventPayload::AcceptedErc20Deposit {
+                    transaction_hash,
+                    block_number,
+                    log_index: _,
+                    from_address,
+                    value,
+                    principal,
+                    erc20_contract_address,
+                    subaccount,
+                } => s.record_accepted_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&transaction_hash, chain_id),
+                    transaction_hash,
+                    block_number,
+                    from_address,
+                    value,
+                    principal,
+                    erc20_contract_address,
+                    subaccount,
                     chain_id,
-                )),
-            AppicEventPayload::FinalizedTransaction {
-                withdrawal_id,
-                transaction_receipt,
-            } => s.record_finalized_icp_to_evm(
-                IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
-                transaction_receipt,
-                icp_to_evm_fee,
-            ),
-            AppicEventPayload::ReimbursedNativeWithdrawal { withdrawal_id, .. } => s
-                .record_reimbursed_icp_to_evm(IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&withdrawal_id),
+                    operator,
+                    event.timestamp,
+                ),
+                AppicEventPayload::InvalidDeposit {
+                    event_source,
+                    reason,
+                } => s.record_invalid_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&event_source.transaction_hash, chain_id),
+                    reason,
+                ),
+                AppicEventPayload::MintedNative {
+                    event_source,
+                    mint_block_index,
+                } => s.record_minted_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&event_source.transaction_hash, chain_id),
+                    evm_to_icp_fee,
+                    nat_to_ledger_mint_index(&mint_block_index),
+                    event.timestamp,
+                ),
+                AppicEventPayload::SyncedToBlock { block_number } => s.record_synced_block(
+                    &minter_key,
+                    SyncedLogType::Native,
+                    nat_to_block_number(block_number),
+                ),
+                AppicEventPayload::SyncedErc20ToBlock { block_number } => s.record_synced_block(
+                    &minter_key,
+                    SyncedLogType::Erc20,
+                    nat_to_block_number(block_number),
+                ),
+                AppicEventPayload::SyncedDepositWithSubaccountToBlock { block_number } => s
+                    .record_synced_block(
+                        &minter_key,
+                        SyncedLogType::DepositWithSubaccount,
+                        nat_to_block_number(block_number),
+                    ),
+                AppicEventPayload::AcceptedNativeWithdrawalRequest {
+                    withdrawal_amount,
+                    destination,
+                    ledger_burn_index,
+                    from,
+                    from_subaccount,
+                    created_at,
+                } => s.record_accepted_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&ledger_burn_index), chain_id),
+                    None,
+                    withdrawal_amount,
+                    NATIVE_ERC20_ADDRESS.to_string(),
+                    destination,
+                    ledger_burn_index,
+                    None,
+                    from,
+                    from_subaccount,
+                    created_at,
+                    operator,
                     chain_id,
-                )),
-            AppicEventPayload::ReimbursedErc20Withdrawal { withdrawal_id, .. } => s
-                .record_reimbursed_icp_to_evm(IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&withdrawal_id),
+                    event.timestamp,
+                ),
+                AppicEventPayload::CreatedTransaction {
+                    withdrawal_id,
+                    transaction,
+                } => s.record_created_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    event_index,
+                    transaction,
+                    event.timestamp,
+                ),
+                AppicEventPayload::SignedTransaction {
+                    withdrawal_id,
+                    raw_transaction,
+                } => s.record_signed_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    event_index,
+                    &raw_transaction,
+                ),
+                AppicEventPayload::ReplacedTransaction {
+                    withdrawal_id,
+                    transaction,
+                } => s.record_replaced_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    event_index,
+                    transaction,
+                    event.timestamp,
+                ),
+                AppicEventPayload::FinalizedTransaction {
+                    withdrawal_id,
+                    transaction_receipt,
+                } => s.record_finalized_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    transaction_receipt,
+                    icp_to_evm_fee,
+                    event.timestamp,
+                ),
+                AppicEventPayload::ReimbursedNativeWithdrawal {
+                    reimbursed_in_block,
+                    withdrawal_id,
+                    reimbursed_amount,
+                    transaction_hash,
+                } => s.record_reimbursed_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    Reimbursement {
+                        reimbursed_amount: nat_to_erc20_amount(reimbursed_amount),
+                        ledger_id: None,
+                        reimbursed_in_block: Some(nat_to_ledger_mint_index(&reimbursed_in_block)),
+                        transaction_hash,
+                    },
+                ),
+                AppicEventPayload::ReimbursedErc20Withdrawal {
+                    withdrawal_id,
+                    reimbursed_in_block,
+                    ledger_id,
+                    reimbursed_amount,
+                    transaction_hash,
+                    ..
+                } => s.record_reimbursed_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    Reimbursement {
+                        reimbursed_amount: nat_to_erc20_amount(reimbursed_amount),
+                        ledger_id: Some(ledger_id),
+                        reimbursed_in_block: Some(nat_to_ledger_mint_index(&reimbursed_in_block)),
+                        transaction_hash,
+                    },
+                ),
+                AppicEventPayload::SkippedBlock { block_number } => {
+                    s.record_skipped_block(&minter_key, nat_to_block_number(block_number))
+                }
+                AppicEventPayload::AddedErc20Token {
                     chain_id,
-                )),
-            AppicEventPayload::SkippedBlock { .. } => {}
-            AppicEventPayload::AddedErc20Token { .. } => {}
-            AppicEventPayload::AcceptedErc20WithdrawalRequest {
-                max_transaction_fee,
-                withdrawal_amount,
-                erc20_contract_address,
-                destination,
-                native_ledger_burn_index,
-                erc20_ledger_burn_index,
-                from,
-                from_subaccount,
-                created_at,
-                ..
-            } => s.record_accepted_icp_to_evm(
-                IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&native_ledger_burn_index),
+                    address,
+                    erc20_token_symbol,
+                    erc20_ledger_id,
+                } => {
+                    if let Ok(address) = Address::from_str(&address) {
+                        let identifier = Erc20Identifier::new(&address, ChainId::from(&chain_id));
+                        match s.record_added_twin_token(
+                            operator,
+                            identifier.clone(),
+                            &erc20_token_symbol,
+                            erc20_ledger_id,
+                            None,
+                        ) {
+                            Some(bridge_pair) => {
+                                log_recorded_bridge_pair(&bridge_pair, &minter_key)
+                            }
+                            // The ledger is not listed yet, the pair waits for its fee
+                            None if s.get_icrc_twin_for_erc20(&identifier, &operator).is_none() => {
+                                PENDING_TWIN_TOKENS.with(|tokens| {
+                                    tokens.borrow_mut().insert(
+                                        (operator, identifier),
+                                        (erc20_token_symbol, erc20_ledger_id),
+                                    )
+                                });
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                AppicEventPayload::AcceptedErc20WithdrawalRequest {
+                    max_transaction_fee,
+                    withdrawal_amount,
+                    erc20_contract_address,
+                    destination,
+                    native_ledger_burn_index,
+                    erc20_ledger_burn_index,
+                    from,
+                    from_subaccount,
+                    created_at,
+                    ..
+                } => s.record_accepted_icp_to_evm(
+                    IcpToEvmIdentifier::new(
+                        nat_to_ledger_burn_index(&native_ledger_burn_index),
+                        chain_id,
+                    ),
+                    Some(max_transaction_fee),
+                    withdrawal_amount,
+                    erc20_contract_address,
+                    destination,
+                    native_ledger_burn_index,
+                    Some(erc20_ledger_burn_index),
+                    from,
+                    from_subaccount,
+                    Some(created_at),
+                    operator,
                     chain_id,
+                    event.timestamp,
                 ),
-                Some(max_transaction_fee),
-                withdrawal_amount,
-                erc20_contract_address,
-                destination,
-                native_ledger_burn_index,
-                Some(erc20_ledger_burn_index),
-                from,
-                from_subaccount,
-                Some(created_at),
-                operator,
-                chain_id,
-                event.timestamp,
+                AppicEventPayload::FailedErc20WithdrawalRequest {
+                    withdrawal_id,
+                    reimbursed_amount,
+                    ..
+                } => s.record_reimbursed_icp_to_evm(
+                    IcpToEvmIdentifier::new(nat_to_ledger_burn_index(&withdrawal_id), chain_id),
+                    Reimbursement {
+                        reimbursed_amount: nat_to_erc20_amount(reimbursed_amount),
+                        ledger_id: None,
+                        reimbursed_in_block: None,
+                        transaction_hash: None,
+                    },
+                ),
+                AppicEventPayload::MintedErc20 {
+                    event_source,
+                    mint_block_index,
+                    ..
+                } => s.record_minted_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&event_source.transaction_hash, chain_id),
+                    evm_to_icp_fee,
+                    nat_to_ledger_mint_index(&mint_block_index),
+                    event.timestamp,
+                ),
+                AppicEventPayload::QuarantinedDeposit { event_source } => s
+                    .record_quarantined_evm_to_icp(EvmToIcpTxIdentifier::new(
+                        &event_source.transaction_hash,
+                        chain_id,
+                    )),
+                AppicEventPayload::QuarantinedReimbursement { index } => s
+                    .record_quarantined_reimbursed_icp_to_evm(IcpToEvmIdentifier::new(
+                        index.into(),
+                        chain_id,
+                    )),
+            }
+
+            if let Some((key, status)) = timeline_entry {
+                s.record_timeline_entry(key, event_index, status, event.timestamp);
+            }
+        });
+    }
+}
+
+// Transaction an event applies to and the status it moves the transaction to
+fn timeline_entry(
+    payload: &AppicEventPayload,
+    chain_id: ChainId,
+) -> Option<(TransactionKey, TimelineStatus)> {
+    let evm_to_icp = |transaction_hash: &String, status: EvmToIcpStatus| {
+        Some((
+            TransactionKey::EvmToIcp(EvmToIcpTxIdentifier::new(transaction_hash, chain_id)),
+            TimelineStatus::EvmToIcp(status),
+        ))
+    };
+    let icp_to_evm = |ledger_burn_index: LedgerBurnIndex, status: IcpToEvmStatus| {
+        Some((
+            TransactionKey::IcpToEvm(IcpToEvmIdentifier::new(ledger_burn_index, chain_id)),
+            TimelineStatus::IcpToEvm(status),
+        ))
+    };
+
+    match payload {
+        AppicEventPayload::AcceptedDeposit {
+            transaction_hash, ..
+        }
+        | AppicEventPayload::AcceptedErc20Deposit {
+            transaction_hash, ..
+        } => evm_to_icp(transaction_hash, EvmToIcpStatus::Accepted),
+        AppicEventPayload::InvalidDeposit {
+            event_source,
+            reason,
+        } => evm_to_icp(
+            &event_source.transaction_hash,
+            EvmToIcpStatus::Invalid(reason.clone()),
+        ),
+        AppicEventPayload::MintedNative { event_source, .. }
+        | AppicEventPayload::MintedErc20 { event_source, .. } => {
+            evm_to_icp(&event_source.transaction_hash, EvmToIcpStatus::Minted)
+        }
+        AppicEventPayload::QuarantinedDeposit { event_source } => {
+            evm_to_icp(&event_source.transaction_hash, EvmToIcpStatus::Quarantined)
+        }
+        AppicEventPayload::AcceptedNativeWithdrawalRequest {
+            ledger_burn_index, ..
+        } => icp_to_evm(
+            nat_to_ledger_burn_index(ledger_burn_index),
+            IcpToEvmStatus::Accepted,
+        ),
+        AppicEventPayload::AcceptedErc20WithdrawalRequest {
+            native_ledger_burn_index,
+            ..
+        } => icp_to_evm(
+            nat_to_ledger_burn_index(native_ledger_burn_index),
+            IcpToEvmStatus::Accepted,
+        ),
+        AppicEventPayload::CreatedTransaction { withdrawal_id, .. } => icp_to_evm(
+            nat_to_ledger_burn_index(withdrawal_id),
+            IcpToEvmStatus::Created,
+        ),
+        AppicEventPayload::SignedTransaction { withdrawal_id, .. } => icp_to_evm(
+            nat_to_ledger_burn_index(withdrawal_id),
+            IcpToEvmStatus::SignedTransaction,
+        ),
+        AppicEventPayload::ReplacedTransaction { withdrawal_id, .. } => icp_to_evm(
+            nat_to_ledger_burn_index(withdrawal_id),
+            IcpToEvmStatus::ReplacedTransaction,
+        ),
+        AppicEventPayload::FinalizedTransaction {
+            withdrawal_id,
+            transaction_receipt,
+        } => icp_to_evm(
+            nat_to_ledger_burn_index(withdrawal_id),
+            match transaction_receipt.status {
+                TransactionStatus::Success => IcpToEvmStatus::Successful,
+                TransactionStatus::Failure => IcpToEvmStatus::Failed,
+            },
+        ),
+        AppicEventPayload::ReimbursedNativeWithdrawal { withdrawal_id, .. }
+        | AppicEventPayload::ReimbursedErc20Withdrawal { withdrawal_id, .. }
+        | AppicEventPayload::FailedErc20WithdrawalRequest { withdrawal_id, .. } => icp_to_evm(
+            nat_to_ledger_burn_index(withdrawal_id),
+            IcpToEvmStatus::Reimbursed,
+        ),
+        AppicEventPayload::QuarantinedReimbursement { index } => icp_to_evm(
+            index.clone().into(),
+            IcpToEvmStatus::QuarantinedReimbursement,
+        ),
+        AppicEventPayload::Init(_)
+        | AppicEventPayload::Upgrade(_)
+        | AppicEventPayload::SyncedToBlock { .. }
+        | AppicEventPayload::SyncedErc20ToBlock { .. }
+        | AppicEventPayload::SyncedDepositWithSubaccountToBlock { .. }
+        | AppicEventPayload::SkippedBlock { .. }
+        | AppicEventPayload::AddedErc20Token { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoints::TransactionSearchParam;
+    use crate::minter_clinet::appic_minter_types::events::{
+        Event as AppicEvent, EventSource, GetEventsResult as AppicGetEventsResult,
+        TransactionReceipt, UnsignedTransaction,
+    };
+    use crate::minter_clinet::dfinity_ck_minter_types::events::{
+        Event as DfinityEvent, EventPayload as DfinityEventPayload,
+        GetEventsResult as DfinityGetEventsResult,
+    };
+    use crate::minter_clinet::mock_runtime::MockRuntime;
+    use crate::minter_clinet::Reason;
+    use crate::numeric::BlockNumber;
+    use crate::numeric::UsdPrice;
+    use crate::state::SyncedBlocks;
+    use candid::{Nat, Principal};
+    use futures::executor::block_on;
+
+    const TX_HASH: &str = "0x8218f324b45a8cd36f38586b062e3884588d926035f08e1dcd3605160b3ebd42";
+
+    fn appic_minter(id: u8, chain_id: u64) -> Minter {
+        Minter {
+            id: Principal::from_slice(&[id]),
+            last_observed_event: 0,
+            last_scraped_event: 0,
+            operator: Operator::AppicMinter,
+            evm_to_icp_fee: Erc20TokenAmount::from(10_u64),
+            icp_to_evm_fee: Erc20TokenAmount::ZERO,
+            chain_id: ChainId(chain_id),
+        }
+    }
+
+    fn deposit_events(total_event_count: u64) -> AppicGetEventsResult {
+        AppicGetEventsResult {
+            events: vec![
+                AppicEvent {
+                    timestamp: 1,
+                    payload: AppicEventPayload::AcceptedDeposit {
+                        transaction_hash: TX_HASH.to_string(),
+                        block_number: Nat::from(100_u64),
+                        log_index: Nat::from(0_u64),
+                        from_address: "0x3bcE376777eCFeb93953cc6C1bB957fbAcb1A261".to_string(),
+                        value: Nat::from(1_000_u64),
+                        principal: Principal::from_slice(&[9]),
+                        subaccount: None,
+                    },
+                },
+                AppicEvent {
+                    timestamp: 2,
+                    payload: AppicEventPayload::MintedNative {
+                        event_source: EventSource {
+                            transaction_hash: TX_HASH.to_string(),
+                            log_index: Nat::from(0_u64),
+                        },
+                        mint_block_index: Nat::from(7_u64),
+                    },
+                },
+            ],
+            total_event_count,
+        }
+    }
+
+    fn events_count(total_event_count: u64) -> AppicGetEventsResult {
+        AppicGetEventsResult {
+            events: vec![],
+            total_event_count,
+        }
+    }
+
+    #[test]
+    fn should_scrape_and_apply_minter_events() {
+        let minter = appic_minter(1, 56);
+        let minter_key = MinterKey::from(&minter);
+        mutate_state(|s| s.record_minter(minter.clone()));
+
+        let runtime = MockRuntime::new();
+        runtime.set_time(42);
+        runtime.add_response(minter.id, "get_events", events_count(3));
+        runtime.add_response(minter.id, "get_events", deposit_events(3));
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        assert_eq!(runtime.pending_responses(), 0);
+
+        let tx = read_state(|s| {
+            s.evm_to_icp_txs
+                .get(&EvmToIcpTxIdentifier::new(
+                    &TX_HASH.to_string(),
+                    ChainId(56),
+                ))
+                .expect("deposit should be recorded")
+        });
+        assert_eq!(tx.status, EvmToIcpStatus::Minted);
+        assert_eq!(tx.actual_received, Some(Erc20TokenAmount::from(990_u64)));
+
+        let minter = read_state(|s| s.minters.get(&minter_key).unwrap());
+        assert_eq!(minter.last_observed_event, 2);
+        assert_eq!(minter.last_scraped_event, 2);
+
+        let health = read_state(|s| s.get_scrape_health(&minter_key));
+        assert_eq!(health.last_success_time, Some(42));
+        assert_eq!(health.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn should_keep_scraping_other_minters_when_one_fails() {
+        let failing = appic_minter(1, 1);
+        let healthy = appic_minter(2, 56);
+        mutate_state(|s| {
+            s.record_minter(failing.clone());
+            s.record_minter(healthy.clone());
+        });
+
+        let runtime = MockRuntime::new();
+        runtime.add_error(
+            failing.id,
+            "get_events",
+            Reason::TransientInternalError("unreachable".to_string()),
+        );
+        runtime.add_response(healthy.id, "get_events", events_count(3));
+        runtime.add_response(healthy.id, "get_events", deposit_events(3));
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        let failing_health = read_state(|s| s.get_scrape_health(&MinterKey::from(&failing)));
+        assert_eq!(failing_health.consecutive_failures, 1);
+
+        let healthy_minter = read_state(|s| s.minters.get(&MinterKey::from(&healthy)).unwrap());
+        assert_eq!(healthy_minter.last_scraped_event, 2);
+        assert!(read_state(|s| s.evm_to_icp_txs.contains_key(
+            &EvmToIcpTxIdentifier::new(&TX_HASH.to_string(), ChainId(56))
+        )));
+    }
+
+    #[test]
+    fn should_record_failed_range_after_repeated_errors() {
+        let minter = appic_minter(1, 56);
+        let minter_key = MinterKey::from(&minter);
+        mutate_state(|s| s.record_minter(minter.clone()));
+
+        // Every get_events call after the count fails
+        let runtime = MockRuntime::new();
+        runtime.set_time(100);
+        runtime.add_response(minter.id, "get_events", events_count(3));
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        let failed_ranges = read_state(|s| s.get_failed_scrape_ranges());
+        assert_eq!(failed_ranges.len(), 1);
+        let (FailedScrapeRangeKey(key, from_event), range) = &failed_ranges[0];
+        assert_eq!(key, &minter_key);
+        assert_eq!(*from_event, 1);
+        assert_eq!(range.to_event, 2);
+        assert_eq!(range.next_retry_time, 100 + failed_range_retry_delay(1));
+        let last_scraped_event =
+            || read_state(|s| s.minters.get(&minter_key).unwrap().last_scraped_event);
+        assert_eq!(last_scraped_event(), 0);
+
+        // Once the backoff expires the range is retried and cleared
+        runtime.set_time(range.next_retry_time);
+        runtime.add_response(minter.id, "get_events", deposit_events(3));
+
+        block_on(retry_failed_scrape_ranges_with_runtime(runtime.clone()));
+
+        assert!(read_state(|s| s.get_failed_scrape_ranges()).is_empty());
+        assert_eq!(last_scraped_event(), 2);
+        assert!(read_state(|s| s.evm_to_icp_txs.contains_key(
+            &EvmToIcpTxIdentifier::new(&TX_HASH.to_string(), ChainId(56))
+        )));
+    }
+
+    #[test]
+    fn should_not_scrape_past_a_failed_range() {
+        let minter = appic_minter(1, 56);
+        let minter_key = MinterKey::from(&minter);
+        mutate_state(|s| s.record_minter(minter.clone()));
+        let mut events = deposit_events(3).events;
+        let minted = events.pop().unwrap();
+        let accepted = events.pop().unwrap();
+        let last_scraped_event =
+            || read_state(|s| s.minters.get(&minter_key).unwrap().last_scraped_event);
+
+        // The range holding the accepted deposit fails
+        let runtime = MockRuntime::new();
+        runtime.set_time(100);
+        runtime.add_response(minter.id, "get_events", events_count(2));
+
+        block_on(scrape_minter(runtime.clone(), minter_key.clone()));
+
+        assert_eq!(last_scraped_event(), 0);
+        assert_eq!(read_state(|s| s.get_failed_scrape_ranges()).len(), 1);
+
+        // The mint arrives while the range is unresolved and is not scraped yet
+        runtime.add_response(minter.id, "get_events", events_count(3));
+
+        block_on(scrape_minter(runtime.clone(), minter_key.clone()));
+
+        assert_eq!(runtime.pending_responses(), 0);
+        assert_eq!(last_scraped_event(), 0);
+
+        // Recovering the range moves the cursor past it
+        runtime.set_time(100 + failed_range_retry_delay(1));
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            AppicGetEventsResult {
+                events: vec![accepted],
+                total_event_count: 3,
+            },
+        );
+
+        block_on(retry_failed_scrape_ranges_with_runtime(runtime.clone()));
+
+        assert!(read_state(|s| s.get_failed_scrape_ranges()).is_empty());
+        assert_eq!(last_scraped_event(), 1);
+
+        // So the mint is applied on top of the accepted deposit
+        runtime.add_response(minter.id, "get_events", events_count(3));
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            AppicGetEventsResult {
+                events: vec![minted],
+                total_event_count: 3,
+            },
+        );
+
+        block_on(scrape_minter(runtime.clone(), minter_key.clone()));
+
+        assert_eq!(last_scraped_event(), 2);
+        let tx = read_state(|s| {
+            s.evm_to_icp_txs
+                .get(&EvmToIcpTxIdentifier::new(
+                    &TX_HASH.to_string(),
+                    ChainId(56),
+                ))
+                .expect("deposit should be recorded")
+        });
+        assert_eq!(tx.status, EvmToIcpStatus::Minted);
+    }
+
+    #[test]
+    fn should_scrape_minters_after_an_idle_one() {
+        // Already up to date, so it is visited first and has nothing to scrape
+        let mut idle = appic_minter(1, 1);
+        idle.last_scraped_event = 2;
+        idle.last_observed_event = 2;
+        let busy = appic_minter(2, 56);
+        mutate_state(|s| {
+            s.record_minter(idle.clone());
+            s.record_minter(busy.clone());
+        });
+
+        let runtime = MockRuntime::new();
+        runtime.add_response(idle.id, "get_events", events_count(3));
+        runtime.add_response(busy.id, "get_events", events_count(3));
+        runtime.add_response(busy.id, "get_events", deposit_events(3));
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        assert_eq!(runtime.pending_responses(), 0);
+        let busy = read_state(|s| s.minters.get(&MinterKey::from(&busy)).unwrap());
+        assert_eq!(busy.last_scraped_event, 2);
+    }
+
+    #[test]
+    fn should_scrape_every_minter_on_its_own_interval() {
+        const SECOND: u64 = 1_000_000_000;
+
+        let fast = appic_minter(1, 1);
+        let slow = appic_minter(2, 56);
+        mutate_state(|s| {
+            s.record_minter(fast.clone());
+            s.record_minter(slow.clone());
+            s.set_scrape_interval(&MinterKey::from(&fast), 60);
+            s.set_scrape_interval(&MinterKey::from(&slow), 600);
+        });
+        let count_calls = |runtime: &MockRuntime, minter: &Minter| {
+            runtime
+                .calls()
+                .iter()
+                .filter(|(canister_id, _method)| *canister_id == minter.id)
+                .count()
+        };
+
+        let runtime = MockRuntime::new();
+        for _ in 0..3 {
+            runtime.add_response(fast.id, "get_events", events_count(1));
+            runtime.add_response(slow.id, "get_events", events_count(1));
+        }
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+        assert_eq!(count_calls(&runtime, &fast), 1);
+        assert_eq!(count_calls(&runtime, &slow), 1);
+
+        // Neither minter is due yet
+        runtime.set_time(30 * SECOND);
+        block_on(scrape_events_with_runtime(runtime.clone()));
+        assert_eq!(count_calls(&runtime, &fast), 1);
+        assert_eq!(count_calls(&runtime, &slow), 1);
+
+        runtime.set_time(60 * SECOND);
+        block_on(scrape_events_with_runtime(runtime.clone()));
+        assert_eq!(count_calls(&runtime, &fast), 2);
+        assert_eq!(count_calls(&runtime, &slow), 1);
+
+        runtime.set_time(600 * SECOND);
+        block_on(scrape_events_with_runtime(runtime.clone()));
+        assert_eq!(count_calls(&runtime, &fast), 3);
+        assert_eq!(count_calls(&runtime, &slow), 2);
+    }
+
+    #[test]
+    fn should_skip_minter_that_is_still_being_scraped() {
+        let busy = appic_minter(1, 1);
+        let other = appic_minter(2, 56);
+        mutate_state(|s| {
+            s.record_minter(busy.clone());
+            s.record_minter(other.clone());
+        });
+
+        let runtime = MockRuntime::new();
+        runtime.add_response(other.id, "get_events", events_count(3));
+        runtime.add_response(other.id, "get_events", deposit_events(3));
+
+        let _in_flight = TimerGuard::new(TaskType::ScrapeMinter(MinterKey::from(&busy))).unwrap();
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        assert!(runtime
+            .calls()
+            .iter()
+            .all(|(canister_id, _method)| *canister_id == other.id));
+        let other = read_state(|s| s.minters.get(&MinterKey::from(&other)).unwrap());
+        assert_eq!(other.last_scraped_event, 2);
+    }
+
+    #[test]
+    fn should_back_off_failing_minters() {
+        const SECOND: u64 = 1_000_000_000;
+
+        assert_eq!(scrape_delay(60, 0), 60 * SECOND);
+        assert_eq!(scrape_delay(60, 1), 120 * SECOND);
+        assert_eq!(scrape_delay(60, 3), 480 * SECOND);
+        assert_eq!(scrape_delay(60, 100), MAX_SCRAPE_BACKOFF_SECONDS * SECOND);
+        // A minter with a long interval is never scraped more often while failing
+        assert_eq!(scrape_delay(2 * 60 * 60, 5), 2 * 60 * 60 * SECOND);
+    }
+
+    fn withdrawal_events() -> Vec<AppicEvent> {
+        let unsigned_transaction = |nonce: u64, max_fee_per_gas: u64| UnsignedTransaction {
+            chain_id: Nat::from(56_u64),
+            nonce: Nat::from(nonce),
+            max_priority_fee_per_gas: Nat::from(1_u64),
+            max_fee_per_gas: Nat::from(max_fee_per_gas),
+            gas_limit: Nat::from(21_000_u64),
+            destination: "0x3bcE376777eCFeb93953cc6C1bB957fbAcb1A261".to_string(),
+            value: Nat::from(900_000_u64),
+            data: Default::default(),
+            access_list: vec![],
+        };
+        let withdrawal_id = || Nat::from(5_u64);
+
+        vec![
+            AppicEventPayload::AcceptedNativeWithdrawalRequest {
+                withdrawal_amount: Nat::from(1_000_000_u64),
+                destination: "0x3bcE376777eCFeb93953cc6C1bB957fbAcb1A261".to_string(),
+                ledger_burn_index: withdrawal_id(),
+                from: Principal::from_slice(&[9]),
+                from_subaccount: None,
+                created_at: Some(10),
+            },
+            AppicEventPayload::CreatedTransaction {
+                withdrawal_id: withdrawal_id(),
+                transaction: unsigned_transaction(0, 10),
+            },
+            AppicEventPayload::SignedTransaction {
+                withdrawal_id: withdrawal_id(),
+                raw_transaction: "0x01".to_string(),
+            },
+            AppicEventPayload::ReplacedTransaction {
+                withdrawal_id: withdrawal_id(),
+                transaction: unsigned_transaction(0, 20),
+            },
+            AppicEventPayload::SignedTransaction {
+                withdrawal_id: withdrawal_id(),
+                raw_transaction: "0x02".to_string(),
+            },
+            AppicEventPayload::FinalizedTransaction {
+                withdrawal_id: withdrawal_id(),
+                transaction_receipt: TransactionReceipt {
+                    block_hash: "0x00".to_string(),
+                    block_number: Nat::from(200_u64),
+                    effective_gas_price: Nat::from(2_u64),
+                    gas_used: Nat::from(21_000_u64),
+                    status: TransactionStatus::Success,
+                    transaction_hash: TX_HASH.to_string(),
+                },
+            },
+        ]
+        .into_iter()
+        .zip(1_u64..)
+        .map(|(payload, timestamp)| AppicEvent {
+            timestamp: timestamp * 100,
+            payload,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn should_record_every_status_of_a_withdrawal_in_its_timeline() {
+        let minter = appic_minter(1, 56);
+        let minter_key = MinterKey::from(&minter);
+        mutate_state(|s| s.record_minter(minter.clone()));
+
+        let events = AppicGetEventsResult {
+            events: withdrawal_events(),
+            total_event_count: 7,
+        };
+        let runtime = MockRuntime::new();
+        runtime.add_response(minter.id, "get_events", events_count(7));
+        runtime.add_response(minter.id, "get_events", events.clone());
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        let expected: Vec<(u64, TimelineStatus, u64)> = vec![
+            (1, TimelineStatus::IcpToEvm(IcpToEvmStatus::Accepted), 100),
+            (2, TimelineStatus::IcpToEvm(IcpToEvmStatus::Created), 200),
+            (
+                3,
+                TimelineStatus::IcpToEvm(IcpToEvmStatus::SignedTransaction),
+                300,
             ),
-            AppicEventPayload::FailedErc20WithdrawalRequest { withdrawal_id, .. } => s
-                .record_reimbursed_icp_to_evm(IcpToEvmIdentifier::new(
-                    nat_to_ledger_burn_index(&withdrawal_id),
-                    chain_id,
-                )),
-            AppicEventPayload::MintedErc20 {
-                event_source,
-                mint_block_index,
-                ..
-            } => s.record_minted_evm_to_icp(
-                EvmToIcpTxIdentifier::new(&event_source.transaction_hash, chain_id),
-                evm_to_icp_fee,
-                nat_to_ledger_mint_index(&mint_block_index),
+            (
+                4,
+                TimelineStatus::IcpToEvm(IcpToEvmStatus::ReplacedTransaction),
+                400,
             ),
-            AppicEventPayload::QuarantinedDeposit { event_source } => s
-                .record_quarantined_evm_to_icp(EvmToIcpTxIdentifier::new(
-                    &event_source.transaction_hash,
-                    chain_id,
-                )),
-            AppicEventPayload::QuarantinedReimbursement { index } => s
-                .record_quarantined_reimbursed_icp_to_evm(IcpToEvmIdentifier::new(
-                    index.into(),
-                    chain_id,
-                )),
+            (
+                5,
+                TimelineStatus::IcpToEvm(IcpToEvmStatus::SignedTransaction),
+                500,
+            ),
+            (6, TimelineStatus::IcpToEvm(IcpToEvmStatus::Successful), 600),
+        ];
+        let timeline = || {
+            read_state(|s| {
+                let key = s
+                    .get_transaction_key_by_search_params(
+                        TransactionSearchParam::TxWithdrawalId(Nat::from(5_u64)),
+                        ChainId(56),
+                    )
+                    .expect("withdrawal should be recorded");
+                s.get_transaction_timeline(&key)
+                    .into_iter()
+                    .map(|(event_index, entry)| (event_index, entry.status, entry.timestamp))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(timeline(), expected);
+
+        // The finalized transaction hash resolves to the same timeline
+        let by_hash = read_state(|s| {
+            s.get_transaction_key_by_search_params(
+                TransactionSearchParam::TxHash(TX_HASH.to_string()),
+                ChainId(56),
+            )
         });
+        assert_eq!(
+            by_hash,
+            Some(TransactionKey::IcpToEvm(IcpToEvmIdentifier::new(
+                LedgerBurnIndex::new(5),
+                ChainId(56)
+            )))
+        );
+
+        // Replaying the events does not duplicate entries
+        runtime.add_response(minter.id, "get_events", events);
+        block_on(rescan_events_range(
+            &MinterClient::with_runtime(runtime.clone(), minter.id, minter.operator),
+            &minter_key,
+            1,
+            6,
+            minter.evm_to_icp_fee,
+            minter.icp_to_evm_fee,
+        ))
+        .unwrap();
+        assert_eq!(timeline(), expected);
+    }
+
+    #[test]
+    fn should_record_every_transaction_attempt_of_a_withdrawal() {
+        const FIRST_HASH: &str =
+            "0x5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2";
+        const REPLACEMENT_HASH: &str =
+            "0xf2ee15ea639b73fa3db9b34a245bdfa015c260c598b211bf05a1ecc4b3e3b4f2";
+
+        let minter = appic_minter(1, 56);
+        let minter_key = MinterKey::from(&minter);
+        mutate_state(|s| s.record_minter(minter.clone()));
+        let identifier = IcpToEvmIdentifier::new(LedgerBurnIndex::new(5), ChainId(56));
+
+        // Every event up to the second signed transaction
+        let mut events = withdrawal_events();
+        let finalized = events.split_off(5);
+        let runtime = MockRuntime::new();
+        runtime.add_response(minter.id, "get_events", events_count(6));
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            AppicGetEventsResult {
+                events,
+                total_event_count: 6,
+            },
+        );
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        let attempts = || {
+            read_state(|s| s.get_transaction_attempts(&identifier))
+                .into_iter()
+                .map(|(event_index, attempt)| {
+                    (
+                        event_index,
+                        attempt.max_fee_per_gas,
+                        attempt.transaction_hash,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = vec![
+            (
+                2,
+                Erc20TokenAmount::from(10_u64),
+                Some(FIRST_HASH.to_string()),
+            ),
+            (
+                4,
+                Erc20TokenAmount::from(20_u64),
+                Some(REPLACEMENT_HASH.to_string()),
+            ),
+        ];
+        assert_eq!(attempts(), expected);
+
+        // The latest signed hash is exposed before the withdrawal is finalized
+        let tx = read_state(|s| s.icp_to_evm_txs.get(&identifier)).unwrap();
+        assert_eq!(tx.transaction_hash, Some(REPLACEMENT_HASH.to_string()));
+        assert_eq!(
+            read_state(|s| s.get_transaction_key_by_search_params(
+                TransactionSearchParam::TxHash(FIRST_HASH.to_string()),
+                ChainId(56),
+            )),
+            Some(TransactionKey::IcpToEvm(identifier.clone()))
+        );
+
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            AppicGetEventsResult {
+                events: finalized,
+                total_event_count: 7,
+            },
+        );
+        block_on(rescan_events_range(
+            &MinterClient::with_runtime(runtime.clone(), minter.id, minter.operator),
+            &minter_key,
+            6,
+            6,
+            minter.evm_to_icp_fee,
+            minter.icp_to_evm_fee,
+        ))
+        .unwrap();
+
+        let tx = read_state(|s| s.icp_to_evm_txs.get(&identifier)).unwrap();
+        assert_eq!(tx.transaction_hash, Some(TX_HASH.to_string()));
+        assert_eq!(attempts(), expected);
+    }
+
+    #[test]
+    fn should_track_synced_and_skipped_blocks_of_dfinity_minter() {
+        let minter = Minter {
+            operator: Operator::DfinityCkEthMinter,
+            ..appic_minter(1, 1)
+        };
+        let minter_key = MinterKey::from(&minter);
+        mutate_state(|s| s.record_minter(minter.clone()));
+
+        let block = |block_number: u64| Nat::from(block_number);
+        let events: Vec<DfinityEvent> = vec![
+            DfinityEventPayload::SyncedToBlock {
+                block_number: block(100),
+            },
+            DfinityEventPayload::SyncedErc20ToBlock {
+                block_number: block(90),
+            },
+            DfinityEventPayload::SkippedBlock {
+                contract_address: None,
+                block_number: block(95),
+            },
+            DfinityEventPayload::SyncedDepositWithSubaccountToBlock {
+                block_number: block(80),
+            },
+            DfinityEventPayload::SyncedToBlock {
+                block_number: block(120),
+            },
+            DfinityEventPayload::SkippedBlock {
+                contract_address: None,
+                block_number: block(93),
+            },
+        ]
+        .into_iter()
+        .map(|payload| DfinityEvent {
+            timestamp: 1,
+            payload,
+        })
+        .collect();
+
+        let runtime = MockRuntime::new();
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            DfinityGetEventsResult {
+                events: vec![],
+                total_event_count: 7,
+            },
+        );
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            DfinityGetEventsResult {
+                events: events.clone(),
+                total_event_count: 7,
+            },
+        );
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        let expected_synced_blocks = SyncedBlocks {
+            native: Some(BlockNumber::from(120_u64)),
+            erc20: Some(BlockNumber::from(90_u64)),
+            deposit_with_subaccount: Some(BlockNumber::from(80_u64)),
+        };
+        let expected_skipped_blocks = vec![BlockNumber::from(93_u64), BlockNumber::from(95_u64)];
+        assert_eq!(
+            read_state(|s| s.get_synced_blocks(&minter_key)),
+            expected_synced_blocks
+        );
+        assert_eq!(
+            read_state(|s| s.get_skipped_blocks(&minter_key)),
+            expected_skipped_blocks
+        );
+
+        // Replaying older events never moves a synced block back
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            DfinityGetEventsResult {
+                events,
+                total_event_count: 7,
+            },
+        );
+        block_on(rescan_events_range(
+            &MinterClient::with_runtime(runtime.clone(), minter.id, minter.operator),
+            &minter_key,
+            1,
+            6,
+            minter.evm_to_icp_fee,
+            minter.icp_to_evm_fee,
+        ))
+        .unwrap();
+        assert_eq!(
+            read_state(|s| s.get_synced_blocks(&minter_key)),
+            expected_synced_blocks
+        );
+        assert_eq!(
+            read_state(|s| s.get_skipped_blocks(&minter_key)),
+            expected_skipped_blocks
+        );
+
+        mutate_state(|s| s.remove_minter(&minter_key));
+        assert_eq!(
+            read_state(|s| s.get_synced_blocks(&minter_key)),
+            SyncedBlocks::default()
+        );
+        assert!(read_state(|s| s.get_skipped_blocks(&minter_key)).is_empty());
+    }
+
+    #[test]
+    fn should_record_bridge_pairs_of_added_twin_tokens() {
+        use crate::state::{EvmToken, IcpToken, IcpTokenType};
+
+        let minter = Minter {
+            operator: Operator::DfinityCkEthMinter,
+            ..appic_minter(1, 1)
+        };
+        mutate_state(|s| s.record_minter(minter.clone()));
+
+        let usdc = Address::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let ckusdc_ledger = Principal::from_text("xevnm-gaaaa-aaaar-qafnq-cai").unwrap();
+        mutate_state(|s| {
+            s.record_evm_token(
+                Erc20Identifier::new(&usdc, ChainId(1)),
+                EvmToken {
+                    chain_id: ChainId(1),
+                    erc20_contract_address: usdc,
+                    name: "USD Coin".to_string(),
+                    decimals: 6,
+                    symbol: "USDC".to_string(),
+                    logo: "".to_string(),
+                },
+            )
+        });
+
+        let events = vec![DfinityEvent {
+            timestamp: 1,
+            payload: DfinityEventPayload::AddedCkErc20Token {
+                chain_id: Nat::from(1_u64),
+                address: usdc.to_string(),
+                ckerc20_token_symbol: "ckUSDC".to_string(),
+                ckerc20_ledger_id: ckusdc_ledger,
+            },
+        }];
+        let runtime = MockRuntime::new();
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            DfinityGetEventsResult {
+                events: vec![],
+                total_event_count: 2,
+            },
+        );
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            DfinityGetEventsResult {
+                events,
+                total_event_count: 2,
+            },
+        );
+        runtime.add_error(
+            ckusdc_ledger,
+            "icrc1_fee",
+            Reason::TransientInternalError("unreachable".to_string()),
+        );
+
+        block_on(scrape_events_with_runtime(runtime.clone()));
+
+        // The ledger is not listed yet, so no pair is published until its fee is known
+        assert!(read_state(|s| s
+            .supported_ckerc20_tokens
+            .get(&Erc20Identifier::new(&usdc, ChainId(1))))
+        .is_none());
+        assert!(read_state(|s| s.get_icp_token_by_principal(&ckusdc_ledger)).is_none());
+
+        runtime.add_response(
+            minter.id,
+            "get_events",
+            DfinityGetEventsResult {
+                events: vec![],
+                total_event_count: 2,
+            },
+        );
+        runtime.add_response(ckusdc_ledger, "icrc1_fee", Nat::from(10_000_u64));
+
+        block_on(scrape_minter(runtime.clone(), MinterKey::from(&minter)));
+
+        // Its token is derived from the erc20 token with the fee of the ledger
+        assert_eq!(runtime.pending_responses(), 0);
+        let pair = read_state(|s| {
+            s.supported_ckerc20_tokens
+                .get(&Erc20Identifier::new(&usdc, ChainId(1)))
+        })
+        .unwrap();
+        assert_eq!(pair.evm_token.symbol, "USDC");
+        assert_eq!(pair.icp_token.ledger_id, ckusdc_ledger);
+        assert_eq!(pair.icp_token.symbol, "ckUSDC");
+        assert_eq!(pair.icp_token.decimals, 6);
+        assert_eq!(pair.icp_token.fee, Erc20TokenAmount::new(10_000));
+        assert!(read_state(|s| s.get_icp_token_by_principal(&ckusdc_ledger)).is_some());
+        assert_eq!(
+            read_state(|s| s.get_icrc_twin_for_erc20(
+                &Erc20Identifier::new(&usdc, ChainId(1)),
+                &Operator::DfinityCkEthMinter
+            )),
+            Some(ckusdc_ledger)
+        );
+
+        // An unknown erc20 token is derived from its listed twin
+        let usdt = Address::from_str("0x55d398326f99059ff775485246999027b3197955").unwrap();
+        let icusdt_ledger = Principal::from_text("cngnf-vqaaa-aaaar-qag4q-cai").unwrap();
+        mutate_state(|s| {
+            s.record_icp_token(
+                icusdt_ledger,
+                IcpToken {
+                    ledger_id: icusdt_ledger,
+                    name: "icUSDT.bsc".to_string(),
+                    decimals: 18,
+                    symbol: "icUSDT.bsc".to_string(),
+                    usd_price: UsdPrice::ZERO,
+                    logo: "".to_string(),
+                    fee: Erc20TokenAmount::new(10_000),
+                    token_type: IcpTokenType::ICRC2,
+                    rank: None,
+                },
+            )
+        });
+        let pair = mutate_state(|s| {
+            s.record_added_twin_token(
+                Operator::AppicMinter,
+                Erc20Identifier::new(&usdt, ChainId(56)),
+                "icUSDT.bsc",
+                icusdt_ledger,
+                None,
+            )
+        })
+        .unwrap();
+        assert_eq!(pair.evm_token.symbol, "USDT");
+        assert_eq!(pair.evm_token.decimals, 18);
+        assert!(mutate_state(|s| s.record_added_twin_token(
+            Operator::AppicMinter,
+            Erc20Identifier::new(&usdt, ChainId(56)),
+            "icUSDT.bsc",
+            icusdt_ledger,
+            None,
+        ))
+        .is_none());
     }
 }
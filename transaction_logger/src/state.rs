@@ -1,39 +1,61 @@
 use crate::numeric::LedgerMintIndex;
 use candid::{CandidType, Nat, Principal};
 use ic_ethereum_types::Address;
+use ic_sha3::Keccak256;
 use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
 use ic_stable_structures::DefaultMemoryImpl;
-use ic_stable_structures::{storable::Bound, BTreeMap, Storable};
+use ic_stable_structures::{BTreeMap, Storable};
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::ops::Bound;
 
 use std::str::FromStr;
 
+use crate::bridge_stats::{
+    aggregate_bridge_stats, day_of, BridgeDirection, BridgeStatsBucket, BridgeStatsKey,
+    TransferStats, TransferUsdValue, UsdVolumeBucket,
+};
 use crate::endpoints::{
-    AddEvmToIcpTx, AddIcpToEvmTx, CandidErc20TwinLedgerSuiteFee, CandidErc20TwinLedgerSuiteStatus,
-    CandidEvmToIcp, CandidEvmToken, CandidIcpToEvm, CandidIcpToken, CandidLedgerSuiteRequest,
-    MinterArgs, TokenPair, Transaction, TransactionSearchParam,
+    AddEvmToIcpTx, AddIcpToEvmTx, CandidBridgeStats, CandidDailyUsdVolume, CandidDepositEta,
+    CandidErc20TwinLedgerSuiteFee, CandidErc20TwinLedgerSuiteStatus, CandidEvmToIcp,
+    CandidEvmToken, CandidIcpToEvm, CandidIcpToken, CandidLedgerSuiteRequest, CandidPricePoint,
+    MinterArgs, StatsWindow, TokenPair, Transaction, TransactionSearchParam, TransactionsPage,
 };
-use crate::numeric::{BlockNumber, Erc20TokenAmount, LedgerBurnIndex};
+use crate::icp_tokens_service::ICP_TOKEN_LOGO_BASE_URL;
+use crate::numeric::{BlockNumber, Erc20TokenAmount, LedgerBurnIndex, UsdPrice, USD_DECIMALS};
+use crate::price_history::{TokenPriceKey, DAILY_PRICE_RETENTION, HOURLY_PRICE_RETENTION};
 use crate::scrape_events::NATIVE_ERC20_ADDRESS;
 
 use std::fmt::Debug;
 
-use crate::minter_clinet::appic_minter_types::events::{TransactionReceipt, TransactionStatus};
+use crate::minter_clinet::appic_minter_types::events::{
+    TransactionReceipt, TransactionStatus, UnsignedTransaction,
+};
 
 mod config;
 
+pub use config::stable_memory_sizes;
+
 use config::{
-    erc20_twin_ledger_requests_id, evm_to_icp_memory, evm_token_list_id, icp_to_evm_memory,
-    icp_token_list_id, minter_memory, supported_appic_tokens_memory_id,
-    supported_ckerc20_tokens_memory_id,
+    address_tx_index_memory, bridge_stats_memory, daily_token_prices_memory,
+    deposit_latencies_memory, disabled_minters_memory, erc20_twin_ledger_requests_id,
+    evm_to_icp_memory, evm_to_icp_mint_index_memory, evm_token_edits_memory, evm_token_list_id,
+    evm_token_prices_memory, failed_scrape_ranges_memory, hourly_token_prices_memory,
+    icp_to_evm_hash_index_memory, icp_to_evm_memory, icp_token_list_id, minter_memory,
+    open_transfers_memory, pending_index_backfill_memory, principal_tx_index_memory,
+    reimbursements_memory, scrape_health_memory, scrape_intervals_memory, skipped_blocks_memory,
+    supported_appic_tokens_memory_id, supported_ckerc20_tokens_memory_id, synced_blocks_memory,
+    transaction_attempts_memory, transaction_status_counts_memory, transaction_time_index_memory,
+    transaction_timelines_memory, transfer_usd_values_memory, usd_volumes_memory,
 };
 
 #[derive(
-    Clone, Copy, CandidType, PartialEq, PartialOrd, Eq, Ord, Debug, Deserialize, Serialize,
+    Clone, Copy, CandidType, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Deserialize, Serialize,
 )]
 pub enum Operator {
     DfinityCkEthMinter,
@@ -84,7 +106,7 @@ impl Minter {
     }
 }
 
-#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Hash, Debug, Deserialize, Serialize)]
 pub struct MinterKey(pub ChainId, pub Operator);
 
 impl MinterKey {
@@ -103,6 +125,75 @@ impl From<&Minter> for MinterKey {
     }
 }
 
+// Type of logs a minter reports to have scraped up to a block
+// Appic minters only report native syncs, which cover every log type
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SyncedLogType {
+    Native,
+    Erc20,
+    DepositWithSubaccount,
+}
+
+// Last evm block scraped by a minter for each type of logs
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct SyncedBlocks {
+    pub native: Option<BlockNumber>,
+    pub erc20: Option<BlockNumber>,
+    pub deposit_with_subaccount: Option<BlockNumber>,
+}
+
+// Evm block a minter skipped, its logs were never scraped
+#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct SkippedBlockKey(pub MinterKey, pub BlockNumber);
+
+impl SyncedBlocks {
+    // Last block the minter scraped for deposits of the token
+    pub fn deposits_synced_to(&self, erc20_contract_address: &Address) -> Option<BlockNumber> {
+        if is_native_token(erc20_contract_address) {
+            self.native
+        } else {
+            self.erc20.or(self.native)
+        }
+    }
+}
+
+// Time in nanoseconds between acceptance and mint of the latest deposits of a minter
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct DepositLatencies {
+    pub samples: Vec<u64>,
+}
+
+impl DepositLatencies {
+    pub fn median(&self) -> Option<u64> {
+        let mut samples = self.samples.clone();
+        samples.sort_unstable();
+        samples.get(samples.len() / 2).copied()
+    }
+}
+
+// First event of a range that could not be scraped
+#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct FailedScrapeRangeKey(pub MinterKey, pub u64);
+
+// Range of events skipped by the scraper, retried with an exponential backoff
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FailedScrapeRange {
+    pub to_event: u64,
+    pub attempts: u32,
+    pub last_error: String,
+    pub last_attempt_time: u64,
+    pub next_retry_time: u64,
+}
+
+// Outcome of the latest calls made while scraping a minter's events
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ScrapeHealth {
+    pub last_success_time: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_error_time: Option<u64>,
+    pub consecutive_failures: u64,
+}
+
 type TransactionHash = String;
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
@@ -130,6 +221,26 @@ pub enum EvmToIcpStatus {
     Quarantined,
 }
 
+impl EvmToIcpStatus {
+    // Position of the status in a deposit's lifecycle, terminal statuses share the last stage
+    fn stage(&self) -> u8 {
+        match self {
+            EvmToIcpStatus::PendingVerification => 0,
+            EvmToIcpStatus::Accepted => 1,
+            EvmToIcpStatus::Minted | EvmToIcpStatus::Invalid(_) | EvmToIcpStatus::Quarantined => 2,
+        }
+    }
+
+    // Status after applying an event, replaying an older event never moves a transaction back
+    pub fn advance_to(&self, next: EvmToIcpStatus) -> EvmToIcpStatus {
+        if next.stage() >= self.stage() {
+            next
+        } else {
+            self.clone()
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
 pub struct EvmToIcpTx {
     pub from_address: Address,
@@ -168,6 +279,138 @@ impl From<&AddIcpToEvmTx> for IcpToEvmIdentifier {
     }
 }
 
+// Evm transaction hash of a finalized icp_to_evm transaction
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct TxHashKey(TransactionHash, ChainId);
+
+// Ledger mint index of a minted evm_to_icp transaction
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct MintIndexKey(LedgerMintIndex, ChainId);
+
+// Identifies a transaction in either direction
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub enum TransactionKey {
+    EvmToIcp(EvmToIcpTxIdentifier),
+    IcpToEvm(IcpToEvmIdentifier),
+}
+
+// Status a transaction moved to when a minter event was applied to it
+#[derive(Clone, CandidType, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+pub enum TimelineStatus {
+    EvmToIcp(EvmToIcpStatus),
+    IcpToEvm(IcpToEvmStatus),
+}
+
+impl TimelineStatus {
+    // Status a transaction is counted under, invalid deposits are counted together whatever
+    // their reason
+    fn counted(self) -> Self {
+        match self {
+            TimelineStatus::EvmToIcp(EvmToIcpStatus::Invalid(_)) => {
+                TimelineStatus::EvmToIcp(EvmToIcpStatus::Invalid(String::new()))
+            }
+            status => status,
+        }
+    }
+}
+
+// Entries of a transaction are ordered by the index of their event on the minter
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct TimelineKey(pub TransactionKey, pub u64);
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TimelineEntry {
+    pub status: TimelineStatus,
+    pub timestamp: u64,
+}
+
+// Attempts of a withdrawal are ordered by the index of the minter event that created them
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct TransactionAttemptKey(pub IcpToEvmIdentifier, pub u64);
+
+// A transaction sent for a withdrawal, replaced transactions are recorded as new attempts
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TransactionAttempt {
+    pub nonce: u64,
+    pub max_fee_per_gas: Erc20TokenAmount,
+    pub max_priority_fee_per_gas: Erc20TokenAmount,
+    pub gas_limit: Erc20TokenAmount,
+    // Known once the transaction is signed
+    pub transaction_hash: Option<TransactionHash>,
+    pub timestamp: u64,
+}
+
+/// Position of a transaction in a paginated history.
+/// Transactions are ordered from newest to oldest and ties are broken by their key,
+/// the cursor is handed to clients as an opaque hex string.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TransactionCursor {
+    pub time: u64,
+    pub key: TransactionKey,
+}
+
+impl TransactionCursor {
+    pub fn new(time: u64, key: TransactionKey) -> Self {
+        Self { time, key }
+    }
+
+    // Position before the newest possible transaction, used to start reading an index
+    fn start() -> Self {
+        Self::new(
+            u64::MAX,
+            TransactionKey::EvmToIcp(EvmToIcpTxIdentifier(String::new(), ChainId(0))),
+        )
+    }
+}
+
+impl Ord for TransactionCursor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .cmp(&self.time)
+            .then_with(|| self.key.cmp(&other.key))
+    }
+}
+
+impl PartialOrd for TransactionCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for TransactionCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = bincode::serialize(self).expect("failed to encode cursor");
+        write!(f, "{}", hex::encode(bytes))
+    }
+}
+
+impl FromStr for TransactionCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|e| format!("invalid cursor: {e}"))?;
+        bincode::deserialize(&bytes).map_err(|e| format!("invalid cursor: {e}"))
+    }
+}
+
+/// Secondary index entry pointing from an account (evm address or principal) to one of its transactions.
+/// Entries of an account are sorted from newest to oldest, so a page is a single range scan.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct TxIndexKey<Account> {
+    pub account: Account,
+    pub cursor: TransactionCursor,
+}
+
+impl<Account> TxIndexKey<Account> {
+    pub fn new(account: Account, cursor: TransactionCursor) -> Self {
+        Self { account, cursor }
+    }
+}
+
+pub type AddressTxIndexKey = TxIndexKey<Address>;
+pub type PrincipalTxIndexKey = TxIndexKey<Principal>;
+
 #[derive(CandidType, Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
 pub enum IcpToEvmStatus {
     PendingVerification,
@@ -182,6 +425,32 @@ pub enum IcpToEvmStatus {
     Failed,
 }
 
+impl IcpToEvmStatus {
+    // Position of the status in a withdrawal's lifecycle
+    // Signed and replaced transactions alternate, so they share a stage
+    fn stage(&self) -> u8 {
+        match self {
+            IcpToEvmStatus::PendingVerification => 0,
+            IcpToEvmStatus::Accepted => 1,
+            IcpToEvmStatus::Created => 2,
+            IcpToEvmStatus::SignedTransaction | IcpToEvmStatus::ReplacedTransaction => 3,
+            IcpToEvmStatus::FinalizedTransaction
+            | IcpToEvmStatus::Successful
+            | IcpToEvmStatus::Failed => 4,
+            IcpToEvmStatus::Reimbursed | IcpToEvmStatus::QuarantinedReimbursement => 5,
+        }
+    }
+
+    // Status after applying an event, replaying an older event never moves a transaction back
+    pub fn advance_to(&self, next: IcpToEvmStatus) -> IcpToEvmStatus {
+        if next.stage() >= self.stage() {
+            next
+        } else {
+            self.clone()
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
 pub struct IcpToEvmTx {
     pub transaction_hash: Option<TransactionHash>,
@@ -205,6 +474,16 @@ pub struct IcpToEvmTx {
     pub operator: Operator,
 }
 
+// Tokens returned to the user on the ICP ledger after a withdrawal failed
+#[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct Reimbursement {
+    pub reimbursed_amount: Erc20TokenAmount,
+    pub ledger_id: Option<Principal>,
+    // Not known for failed erc20 withdrawal requests until the reimbursement is minted
+    pub reimbursed_in_block: Option<LedgerMintIndex>,
+    pub transaction_hash: Option<TransactionHash>,
+}
+
 #[derive(Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
 pub struct Erc20Identifier(pub Address, pub ChainId);
 
@@ -237,6 +516,23 @@ pub struct EvmToken {
     pub logo: String,
 }
 
+// Change made to an evm token through the token registry endpoints
+// Tokens with an edit are managed at runtime, the bundled token lists never overwrite them
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub enum EvmTokenEdit {
+    Upserted,
+    Hidden,
+    // The token was removed from the list and is not added back by the bundled token lists
+    Deleted,
+}
+
+// Usd price of an evm token pushed by a controller, used for tokens without a priced icp twin
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct EvmTokenPrice {
+    pub usd_price: UsdPrice,
+    pub updated_at: u64,
+}
+
 #[derive(CandidType, Clone, PartialEq, Ord, Eq, PartialOrd, Debug, Deserialize, Serialize)]
 pub enum IcpTokenType {
     ICRC1,
@@ -252,7 +548,8 @@ pub struct IcpToken {
     pub name: String,
     pub decimals: u8,
     pub symbol: String,
-    pub usd_price: String,
+    #[serde(with = "usd_price_as_decimal_str")]
+    pub usd_price: UsdPrice,
     pub logo: String,
     pub fee: Erc20TokenAmount,
     pub token_type: IcpTokenType,
@@ -272,6 +569,72 @@ impl Hash for IcpToken {
     }
 }
 
+// Icp tokens are stored with their usd price as a decimal string, the layout they had before
+// prices were fixed point, so stored tokens and bridge pairs decode unchanged
+// A stored string that is not a price decodes as zero, an unknown price, until the next update
+mod usd_price_as_decimal_str {
+    use super::{UsdPrice, USD_DECIMALS};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        usd_price: &UsdPrice,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&usd_price.to_decimal_string(USD_DECIMALS))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UsdPrice, D::Error> {
+        let usd_price = String::deserialize(deserializer)?;
+        Ok(UsdPrice::from_decimal_str(&usd_price, USD_DECIMALS).unwrap_or(UsdPrice::ZERO))
+    }
+}
+
+impl IcpToken {
+    // Twin ledgers have the decimals of their erc20 token, the fee is queried from the ledger
+    pub fn twin_of(
+        evm_token: &EvmToken,
+        ledger_id: Principal,
+        twin_symbol: &str,
+        fee: Erc20TokenAmount,
+    ) -> Self {
+        Self {
+            ledger_id,
+            name: twin_symbol.to_string(),
+            decimals: evm_token.decimals,
+            symbol: twin_symbol.to_string(),
+            usd_price: UsdPrice::ZERO,
+            logo: format!("{}{}", ICP_TOKEN_LOGO_BASE_URL, ledger_id),
+            fee,
+            token_type: IcpTokenType::ICRC2,
+            rank: None,
+        }
+    }
+}
+
+impl EvmToken {
+    pub fn twin_of(icp_token: &IcpToken, identifier: &Erc20Identifier, twin_symbol: &str) -> Self {
+        let symbol = erc20_symbol_of_twin(twin_symbol);
+        Self {
+            chain_id: identifier.chain_id(),
+            erc20_contract_address: identifier.erc20_address(),
+            name: symbol.clone(),
+            decimals: icp_token.decimals,
+            symbol,
+            logo: String::new(),
+        }
+    }
+}
+
+// Symbol of the erc20 token a twin was created for, ckUSDC -> USDC and icUSDT.bsc -> USDT
+fn erc20_symbol_of_twin(twin_symbol: &str) -> String {
+    let symbol = twin_symbol.split('.').next().unwrap_or(twin_symbol);
+    symbol
+        .strip_prefix("ck")
+        .or_else(|| symbol.strip_prefix("ic"))
+        .unwrap_or(symbol)
+        .to_string()
+}
+
 #[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Debug, Deserialize, Serialize)]
 pub struct BridgePair {
     pub icp_token: IcpToken,
@@ -344,6 +707,78 @@ pub struct State {
 
     // List of new erc20 -> icERC20 requests
     pub twin_erc20_requests: BTreeMap<Erc20Identifier, Erc20TwinLedgerSuiteRequest, StableMemory>,
+
+    // Secondary indexes of transactions by evm address and by principal
+    // Kept in sync by record_new_evm_to_icp and record_new_icp_to_evm
+    pub address_tx_index: BTreeMap<AddressTxIndexKey, (), StableMemory>,
+    pub principal_tx_index: BTreeMap<PrincipalTxIndexKey, (), StableMemory>,
+
+    // Transactions of every account from newest to oldest, kept in sync with the indexes above
+    pub transaction_time_index: BTreeMap<TransactionCursor, (), StableMemory>,
+
+    // Number of stored transactions per status, exported by the metrics
+    pub transaction_status_counts: BTreeMap<TimelineStatus, u64, StableMemory>,
+
+    // Reverse indexes for searching transactions by evm hash and by ledger mint index
+    pub icp_to_evm_hash_index: BTreeMap<TxHashKey, IcpToEvmIdentifier, StableMemory>,
+    pub evm_to_icp_mint_index: BTreeMap<MintIndexKey, EvmToIcpTxIdentifier, StableMemory>,
+
+    // Next transaction to index while transactions recorded before the indexes existed are
+    // indexed, empty once they all are
+    pub pending_index_backfill: BTreeMap<(), TransactionKey, StableMemory>,
+
+    // Result of the latest scraping attempts for every minter
+    pub scrape_health: BTreeMap<MinterKey, ScrapeHealth, StableMemory>,
+
+    // Minters that are kept in state but no longer scraped
+    pub disabled_minters: BTreeMap<MinterKey, (), StableMemory>,
+
+    // Event ranges that were skipped by the scraper and are waiting for a retry
+    pub failed_scrape_ranges: BTreeMap<FailedScrapeRangeKey, FailedScrapeRange, StableMemory>,
+
+    // Scraping interval in seconds of minters that do not use the default one
+    pub scrape_intervals: BTreeMap<MinterKey, u64, StableMemory>,
+
+    // Every status a transaction went through, one entry per applied minter event
+    pub transaction_timelines: BTreeMap<TimelineKey, TimelineEntry, StableMemory>,
+
+    // Every transaction sent for a withdrawal, kept apart from icp_to_evm_txs
+    // so already stored withdrawals decode unchanged
+    pub transaction_attempts: BTreeMap<TransactionAttemptKey, TransactionAttempt, StableMemory>,
+
+    // Tokens returned for failed withdrawals, kept apart from icp_to_evm_txs like the attempts
+    pub reimbursements: BTreeMap<IcpToEvmIdentifier, Reimbursement, StableMemory>,
+
+    // Last evm blocks every minter reported as synced
+    pub synced_blocks: BTreeMap<MinterKey, SyncedBlocks, StableMemory>,
+
+    // Evm blocks skipped by every minter
+    pub skipped_blocks: BTreeMap<SkippedBlockKey, (), StableMemory>,
+
+    // Latest deposit latencies of every minter, used to estimate when pending deposits are minted
+    pub deposit_latencies: BTreeMap<MinterKey, DepositLatencies, StableMemory>,
+
+    // Daily transfer statistics of every operator and token
+    pub bridge_stats: BTreeMap<BridgeStatsKey, BridgeStatsBucket, StableMemory>,
+
+    // Transfers counted as accepted in bridge_stats that did not complete or fail yet
+    pub open_transfers: BTreeMap<TransactionKey, (), StableMemory>,
+
+    // Usd value of every transfer that could be valued when it was accepted
+    pub transfer_usd_values: BTreeMap<TransactionKey, TransferUsdValue, StableMemory>,
+
+    // Daily usd volume of every operator and token
+    pub usd_volumes: BTreeMap<BridgeStatsKey, UsdVolumeBucket, StableMemory>,
+
+    // Usd price series of every icp token, see price_history
+    pub hourly_token_prices: BTreeMap<TokenPriceKey, UsdPrice, StableMemory>,
+    pub daily_token_prices: BTreeMap<TokenPriceKey, UsdPrice, StableMemory>,
+
+    // Controller pushed usd prices of evm tokens
+    pub evm_token_prices: BTreeMap<Erc20Identifier, EvmTokenPrice, StableMemory>,
+
+    // Evm tokens managed through the token registry endpoints
+    pub evm_token_edits: BTreeMap<Erc20Identifier, EvmTokenEdit, StableMemory>,
 }
 
 impl State {
@@ -387,6 +822,209 @@ impl State {
         self.minters.iter().collect()
     }
 
+    // Minters whose events should be scraped
+    pub fn get_active_minters(&self) -> Vec<(MinterKey, Minter)> {
+        self.minters
+            .iter()
+            .filter(|(minter_key, _minter)| !self.disabled_minters.contains_key(minter_key))
+            .collect()
+    }
+
+    pub fn if_minter_exists(&self, minter_key: &MinterKey) -> bool {
+        self.minters.contains_key(minter_key)
+    }
+
+    pub fn is_minter_enabled(&self, minter_key: &MinterKey) -> bool {
+        !self.disabled_minters.contains_key(minter_key)
+    }
+
+    pub fn disable_minter(&mut self, minter_key: &MinterKey) {
+        self.disabled_minters.insert(minter_key.clone(), ());
+    }
+
+    pub fn enable_minter(&mut self, minter_key: &MinterKey) {
+        self.disabled_minters.remove(minter_key);
+    }
+
+    // Seconds between two scraping rounds of the minter, SCRAPE_EVENTS unless overridden
+    pub fn get_scrape_interval(&self, minter_key: &MinterKey) -> u64 {
+        self.scrape_intervals
+            .get(minter_key)
+            .unwrap_or(crate::SCRAPE_EVENTS.as_secs())
+    }
+
+    pub fn set_scrape_interval(&mut self, minter_key: &MinterKey, interval_seconds: u64) {
+        self.scrape_intervals
+            .insert(minter_key.clone(), interval_seconds);
+    }
+
+    // Removes a minter together with its scraping state
+    // Transactions that were scraped from the minter are kept
+    pub fn remove_minter(&mut self, minter_key: &MinterKey) -> Option<Minter> {
+        self.scrape_health.remove(minter_key);
+        self.disabled_minters.remove(minter_key);
+        self.scrape_intervals.remove(minter_key);
+        self.remove_failed_scrape_ranges_within(minter_key, 0, u64::MAX);
+        self.synced_blocks.remove(minter_key);
+        self.deposit_latencies.remove(minter_key);
+        for skipped_block in self.get_skipped_blocks(minter_key) {
+            self.skipped_blocks
+                .remove(&SkippedBlockKey(minter_key.clone(), skipped_block));
+        }
+        self.minters.remove(minter_key)
+    }
+
+    // Synced blocks only move forward, so replaying older events keeps the latest one
+    pub fn record_synced_block(
+        &mut self,
+        minter_key: &MinterKey,
+        log_type: SyncedLogType,
+        block_number: BlockNumber,
+    ) {
+        let mut synced_blocks = self.get_synced_blocks(minter_key);
+        let synced_block = match log_type {
+            SyncedLogType::Native => &mut synced_blocks.native,
+            SyncedLogType::Erc20 => &mut synced_blocks.erc20,
+            SyncedLogType::DepositWithSubaccount => &mut synced_blocks.deposit_with_subaccount,
+        };
+        *synced_block = Some(synced_block.map_or(block_number, |block| block.max(block_number)));
+
+        self.synced_blocks.insert(minter_key.clone(), synced_blocks);
+    }
+
+    pub fn get_synced_blocks(&self, minter_key: &MinterKey) -> SyncedBlocks {
+        self.synced_blocks.get(minter_key).unwrap_or_default()
+    }
+
+    pub fn record_skipped_block(&mut self, minter_key: &MinterKey, block_number: BlockNumber) {
+        self.skipped_blocks
+            .insert(SkippedBlockKey(minter_key.clone(), block_number), ());
+    }
+
+    // Skipped blocks of a minter in ascending order
+    pub fn get_skipped_blocks(&self, minter_key: &MinterKey) -> Vec<BlockNumber> {
+        self.skipped_blocks
+            .range(
+                SkippedBlockKey(minter_key.clone(), BlockNumber::ZERO)
+                    ..=SkippedBlockKey(minter_key.clone(), BlockNumber::MAX),
+            )
+            .map(|(SkippedBlockKey(_minter_key, block_number), ())| block_number)
+            .collect()
+    }
+
+    // Records a range that could not be scraped, or a failed retry of an already recorded one
+    pub fn record_failed_scrape_range(
+        &mut self,
+        minter_key: &MinterKey,
+        from_event: u64,
+        to_event: u64,
+        error: String,
+        time: u64,
+        retry_delay: impl Fn(u32) -> u64,
+    ) {
+        let key = FailedScrapeRangeKey(minter_key.clone(), from_event);
+        let attempts = self
+            .failed_scrape_ranges
+            .get(&key)
+            .map(|range| range.attempts)
+            .unwrap_or_default()
+            .saturating_add(1);
+
+        self.failed_scrape_ranges.insert(
+            key,
+            FailedScrapeRange {
+                to_event,
+                attempts,
+                last_error: error,
+                last_attempt_time: time,
+                next_retry_time: time.saturating_add(retry_delay(attempts)),
+            },
+        );
+    }
+
+    pub fn remove_failed_scrape_range(&mut self, minter_key: &MinterKey, from_event: u64) {
+        self.failed_scrape_ranges
+            .remove(&FailedScrapeRangeKey(minter_key.clone(), from_event));
+    }
+
+    // Removes the failed ranges that are fully covered by from_event..=to_event
+    pub fn remove_failed_scrape_ranges_within(
+        &mut self,
+        minter_key: &MinterKey,
+        from_event: u64,
+        to_event: u64,
+    ) {
+        let covered: Vec<FailedScrapeRangeKey> = self
+            .failed_scrape_ranges
+            .range(FailedScrapeRangeKey(minter_key.clone(), from_event)..)
+            .take_while(|(key, _range)| &key.0 == minter_key)
+            .filter(|(_key, range)| range.to_event <= to_event)
+            .map(|(key, _range)| key)
+            .collect();
+
+        for key in covered {
+            self.failed_scrape_ranges.remove(&key);
+        }
+    }
+
+    // Whether the minter has a failed range that starts after last_scraped_event, i.e. a range
+    // whose events were never applied
+    pub fn has_unresolved_failed_scrape_range(
+        &self,
+        minter_key: &MinterKey,
+        last_scraped_event: u64,
+    ) -> bool {
+        self.failed_scrape_ranges
+            .range(FailedScrapeRangeKey(minter_key.clone(), last_scraped_event.saturating_add(1))..)
+            .next()
+            .is_some_and(|(key, _range)| &key.0 == minter_key)
+    }
+
+    pub fn get_failed_scrape_ranges(&self) -> Vec<(FailedScrapeRangeKey, FailedScrapeRange)> {
+        self.failed_scrape_ranges.iter().collect()
+    }
+
+    // Failed ranges whose backoff has expired
+    pub fn get_due_failed_scrape_ranges(
+        &self,
+        now: u64,
+    ) -> Vec<(FailedScrapeRangeKey, FailedScrapeRange)> {
+        self.failed_scrape_ranges
+            .iter()
+            .filter(|(_key, range)| range.next_retry_time <= now)
+            .collect()
+    }
+
+    pub fn get_scrape_health(&self, minter_key: &MinterKey) -> ScrapeHealth {
+        self.scrape_health.get(minter_key).unwrap_or_default()
+    }
+
+    // Records a successful call to the minter, resetting the failure streak
+    pub fn record_scrape_success(&mut self, minter_key: &MinterKey, time: u64) {
+        let health = self.get_scrape_health(minter_key);
+        self.scrape_health.insert(
+            minter_key.clone(),
+            ScrapeHealth {
+                last_success_time: Some(time),
+                consecutive_failures: 0,
+                ..health
+            },
+        );
+    }
+
+    pub fn record_scrape_failure(&mut self, minter_key: &MinterKey, error: String, time: u64) {
+        let health = self.get_scrape_health(minter_key);
+        self.scrape_health.insert(
+            minter_key.clone(),
+            ScrapeHealth {
+                last_error: Some(error),
+                last_error_time: Some(time),
+                consecutive_failures: health.consecutive_failures + 1,
+                ..health
+            },
+        );
+    }
+
     pub fn if_chain_id_exists(&self, chain_id: ChainId) -> bool {
         for (_minter_key, minter) in self.get_minters() {
             if minter.chain_id == chain_id {
@@ -426,7 +1064,27 @@ impl State {
     }
 
     pub fn record_new_evm_to_icp(&mut self, identifier: EvmToIcpTxIdentifier, tx: EvmToIcpTx) {
-        self.evm_to_icp_txs.insert(identifier, tx);
+        let key = TransactionKey::EvmToIcp(identifier.clone());
+        let (address, principal, time) = (tx.from_address, tx.principal, tx.time);
+        let status = TimelineStatus::EvmToIcp(tx.status.clone());
+
+        let previous_tx = self.evm_to_icp_txs.insert(identifier, tx);
+        self.update_status_counts(
+            &key,
+            previous_tx
+                .as_ref()
+                .map(|previous_tx| TimelineStatus::EvmToIcp(previous_tx.status.clone())),
+            Some(status),
+        );
+        if let Some(previous_tx) = previous_tx {
+            self.unindex_transaction(
+                key.clone(),
+                previous_tx.from_address,
+                previous_tx.principal,
+                previous_tx.time,
+            );
+        }
+        self.index_transaction(key, address, principal, time);
     }
 
     pub fn record_accepted_evm_to_icp(
@@ -450,6 +1108,8 @@ impl State {
             .expect("Should not fail converting erc20_contract_address to Address");
 
         if let Some(tx) = self.evm_to_icp_txs.get(&identifier) {
+            let newly_accepted = tx.status == EvmToIcpStatus::PendingVerification;
+
             // Update only the necessary fields in the existing transaction
             let new_tx = EvmToIcpTx {
                 verified: true,
@@ -459,9 +1119,23 @@ impl State {
                 principal,
                 erc20_contract_address: parsed_erc20_address,
                 subaccount,
-                status: EvmToIcpStatus::Accepted,
+                status: tx.status.advance_to(EvmToIcpStatus::Accepted),
                 ..tx
             };
+
+            if newly_accepted {
+                self.record_accepted_transfer(
+                    TransactionKey::EvmToIcp(identifier.clone()),
+                    BridgeDirection::EvmToIcp,
+                    BridgeStatsKey::new(
+                        new_tx.time,
+                        new_tx.operator,
+                        Erc20Identifier(new_tx.erc20_contract_address, new_tx.chain_id),
+                    ),
+                    new_tx.icrc_ledger_id,
+                    new_tx.value,
+                );
+            }
             self.record_new_evm_to_icp(identifier, new_tx);
         } else {
             // Create a new transaction only if one doses not already exist
@@ -487,6 +1161,17 @@ impl State {
                 ledger_mint_index: None,
             };
 
+            self.record_accepted_transfer(
+                TransactionKey::EvmToIcp(identifier.clone()),
+                BridgeDirection::EvmToIcp,
+                BridgeStatsKey::new(
+                    timestamp,
+                    operator,
+                    Erc20Identifier(parsed_erc20_address, chain_id),
+                ),
+                new_tx.icrc_ledger_id,
+                new_tx.value,
+            );
             self.record_new_evm_to_icp(identifier, new_tx);
         }
     }
@@ -496,8 +1181,25 @@ impl State {
         identifier: EvmToIcpTxIdentifier,
         evm_to_icp_fee: Erc20TokenAmount,
         ledger_mint_index: LedgerMintIndex,
+        minted_at: u64,
     ) {
+        self.record_deposit_latency(&identifier, minted_at);
+
         if let Some(tx) = self.evm_to_icp_txs.get(&identifier) {
+            if tx.status == EvmToIcpStatus::Accepted {
+                let accepted_at = self
+                    .get_deposit_accepted_time(&identifier)
+                    .unwrap_or(tx.time);
+                self.record_closed_transfer(
+                    TransactionKey::EvmToIcp(identifier.clone()),
+                    BridgeDirection::EvmToIcp,
+                    tx.operator,
+                    Erc20Identifier(tx.erc20_contract_address, tx.chain_id),
+                    tx.time,
+                    |stats| stats.record_completed(minted_at.saturating_sub(accepted_at)),
+                );
+            }
+
             // Fee calculation
             let actual_received = if is_native_token(&tx.erc20_contract_address) {
                 Some(tx.value.checked_sub(evm_to_icp_fee).unwrap_or(tx.value))
@@ -505,11 +1207,16 @@ impl State {
                 Some(tx.value)
             };
 
+            self.evm_to_icp_mint_index.insert(
+                MintIndexKey(ledger_mint_index, tx.chain_id),
+                identifier.clone(),
+            );
+
             // Transaction update
             let new_tx = EvmToIcpTx {
                 actual_received,
                 ledger_mint_index: Some(ledger_mint_index),
-                status: EvmToIcpStatus::Minted,
+                status: tx.status.advance_to(EvmToIcpStatus::Minted),
                 ..tx
             };
             self.record_new_evm_to_icp(identifier, new_tx);
@@ -518,8 +1225,9 @@ impl State {
 
     pub fn record_invalid_evm_to_icp(&mut self, identifier: EvmToIcpTxIdentifier, reason: String) {
         if let Some(tx) = self.evm_to_icp_txs.get(&identifier) {
+            self.record_failed_evm_to_icp_stats(&identifier, &tx);
             let new_tx = EvmToIcpTx {
-                status: EvmToIcpStatus::Invalid(reason),
+                status: tx.status.advance_to(EvmToIcpStatus::Invalid(reason)),
                 ..tx
             };
             self.record_new_evm_to_icp(identifier, new_tx);
@@ -528,16 +1236,55 @@ impl State {
 
     pub fn record_quarantined_evm_to_icp(&mut self, identifier: EvmToIcpTxIdentifier) {
         if let Some(tx) = self.evm_to_icp_txs.get(&identifier) {
+            self.record_failed_evm_to_icp_stats(&identifier, &tx);
             let new_tx = EvmToIcpTx {
-                status: EvmToIcpStatus::Quarantined,
+                status: tx.status.advance_to(EvmToIcpStatus::Quarantined),
                 ..tx
             };
             self.record_new_evm_to_icp(identifier, new_tx);
         }
     }
 
+    // Deposits are counted as failed only once and only if they were counted as accepted
+    fn record_failed_evm_to_icp_stats(
+        &mut self,
+        identifier: &EvmToIcpTxIdentifier,
+        tx: &EvmToIcpTx,
+    ) {
+        if tx.status == EvmToIcpStatus::Accepted {
+            self.record_closed_transfer(
+                TransactionKey::EvmToIcp(identifier.clone()),
+                BridgeDirection::EvmToIcp,
+                tx.operator,
+                Erc20Identifier(tx.erc20_contract_address, tx.chain_id),
+                tx.time,
+                TransferStats::record_failed,
+            );
+        }
+    }
+
     pub fn record_new_icp_to_evm(&mut self, identifier: IcpToEvmIdentifier, tx: IcpToEvmTx) {
-        self.icp_to_evm_txs.insert(identifier, tx);
+        let key = TransactionKey::IcpToEvm(identifier.clone());
+        let (address, principal, time) = (tx.destination, tx.from, tx.time);
+        let status = TimelineStatus::IcpToEvm(tx.status.clone());
+
+        let previous_tx = self.icp_to_evm_txs.insert(identifier, tx);
+        self.update_status_counts(
+            &key,
+            previous_tx
+                .as_ref()
+                .map(|previous_tx| TimelineStatus::IcpToEvm(previous_tx.status.clone())),
+            Some(status),
+        );
+        if let Some(previous_tx) = previous_tx {
+            self.unindex_transaction(
+                key.clone(),
+                previous_tx.destination,
+                previous_tx.from,
+                previous_tx.time,
+            );
+        }
+        self.index_transaction(key, address, principal, time);
     }
 
     pub fn record_accepted_icp_to_evm(
@@ -570,6 +1317,20 @@ impl State {
             erc20_ledger_burn_index.map(|burn_index| LedgerBurnIndex::new(nat_to_u64(&burn_index)));
 
         if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            if tx.status == IcpToEvmStatus::PendingVerification {
+                self.record_accepted_transfer(
+                    TransactionKey::IcpToEvm(identifier.clone()),
+                    BridgeDirection::IcpToEvm,
+                    BridgeStatsKey::new(
+                        tx.time,
+                        operator,
+                        Erc20Identifier(erc20_address, chain_id),
+                    ),
+                    tx.icrc_ledger_id,
+                    withdrawal_amount,
+                );
+            }
+
             let new_tx = IcpToEvmTx {
                 verified: true,
                 max_transaction_fee,
@@ -580,7 +1341,7 @@ impl State {
                 erc20_ledger_burn_index,
                 from,
                 from_subaccount,
-                status: IcpToEvmStatus::Accepted,
+                status: tx.status.advance_to(IcpToEvmStatus::Accepted),
                 ..tx
             };
 
@@ -611,47 +1372,170 @@ impl State {
                 total_gas_spent: None,
             };
 
+            self.record_accepted_transfer(
+                TransactionKey::IcpToEvm(identifier.clone()),
+                BridgeDirection::IcpToEvm,
+                BridgeStatsKey::new(
+                    new_tx.time,
+                    operator,
+                    Erc20Identifier(erc20_address, chain_id),
+                ),
+                icrc_ledger_id,
+                withdrawal_amount,
+            );
             self.record_new_icp_to_evm(identifier, new_tx);
         }
     }
 
-    pub fn record_created_icp_to_evm(&mut self, identifier: IcpToEvmIdentifier) {
+    pub fn record_created_icp_to_evm(
+        &mut self,
+        identifier: IcpToEvmIdentifier,
+        event_index: u64,
+        transaction: UnsignedTransaction,
+        timestamp: u64,
+    ) {
         if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            self.record_transaction_attempt(&identifier, event_index, transaction, timestamp);
             let new_tx = IcpToEvmTx {
-                status: IcpToEvmStatus::Created,
+                status: tx.status.advance_to(IcpToEvmStatus::Created),
                 ..tx
             };
             self.record_new_icp_to_evm(identifier, new_tx);
         }
     }
 
-    pub fn record_signed_icp_to_evm(&mut self, identifier: IcpToEvmIdentifier) {
+    // Attaches the hash of the signed transaction to the latest attempt created before it,
+    // until the withdrawal is finalized the hash is also exposed as its transaction hash
+    pub fn record_signed_icp_to_evm(
+        &mut self,
+        identifier: IcpToEvmIdentifier,
+        event_index: u64,
+        raw_transaction: &str,
+    ) {
         if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            let status = tx.status.advance_to(IcpToEvmStatus::SignedTransaction);
+            let mut transaction_hash = tx.transaction_hash.clone();
+
+            if let Some(hash) = signed_transaction_hash(raw_transaction) {
+                if let Some((key, attempt)) = self
+                    .transaction_attempts
+                    .range(
+                        TransactionAttemptKey(identifier.clone(), 0)
+                            ..=TransactionAttemptKey(identifier.clone(), event_index),
+                    )
+                    .next_back()
+                {
+                    self.transaction_attempts.insert(
+                        key,
+                        TransactionAttempt {
+                            transaction_hash: Some(hash.clone()),
+                            ..attempt
+                        },
+                    );
+                }
+
+                self.icp_to_evm_hash_index
+                    .insert(TxHashKey(hash.clone(), tx.chain_id), identifier.clone());
+
+                if status == IcpToEvmStatus::SignedTransaction {
+                    transaction_hash = Some(hash);
+                }
+            }
+
             let new_tx = IcpToEvmTx {
-                status: IcpToEvmStatus::SignedTransaction,
+                status,
+                transaction_hash,
                 ..tx
             };
             self.record_new_icp_to_evm(identifier, new_tx);
         }
     }
 
-    pub fn record_replaced_icp_to_evm(&mut self, identifier: IcpToEvmIdentifier) {
-        if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+    pub fn record_replaced_icp_to_evm(
+        &mut self,
+        identifier: IcpToEvmIdentifier,
+        event_index: u64,
+        transaction: UnsignedTransaction,
+        timestamp: u64,
+    ) {
+        if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            self.record_transaction_attempt(&identifier, event_index, transaction, timestamp);
             let new_tx = IcpToEvmTx {
-                status: IcpToEvmStatus::ReplacedTransaction,
+                status: tx.status.advance_to(IcpToEvmStatus::ReplacedTransaction),
                 ..tx
             };
             self.record_new_icp_to_evm(identifier, new_tx);
         }
     }
 
+    // Replaying the same event keeps the hash of an already signed attempt
+    fn record_transaction_attempt(
+        &mut self,
+        identifier: &IcpToEvmIdentifier,
+        event_index: u64,
+        transaction: UnsignedTransaction,
+        timestamp: u64,
+    ) {
+        let key = TransactionAttemptKey(identifier.clone(), event_index);
+        let transaction_hash = self
+            .transaction_attempts
+            .get(&key)
+            .and_then(|attempt| attempt.transaction_hash);
+
+        self.transaction_attempts.insert(
+            key,
+            TransactionAttempt {
+                nonce: nat_to_u64(&transaction.nonce),
+                max_fee_per_gas: nat_to_erc20_amount(transaction.max_fee_per_gas),
+                max_priority_fee_per_gas: nat_to_erc20_amount(transaction.max_priority_fee_per_gas),
+                gas_limit: nat_to_erc20_amount(transaction.gas_limit),
+                transaction_hash,
+                timestamp,
+            },
+        );
+    }
+
+    // Transactions sent for a withdrawal ordered by minter event index
+    pub fn get_transaction_attempts(
+        &self,
+        identifier: &IcpToEvmIdentifier,
+    ) -> Vec<(u64, TransactionAttempt)> {
+        self.transaction_attempts
+            .range(
+                TransactionAttemptKey(identifier.clone(), 0)
+                    ..=TransactionAttemptKey(identifier.clone(), u64::MAX),
+            )
+            .map(
+                |(TransactionAttemptKey(_identifier, event_index), attempt)| (event_index, attempt),
+            )
+            .collect()
+    }
+
     pub fn record_finalized_icp_to_evm(
         &mut self,
         identifier: IcpToEvmIdentifier,
         receipt: TransactionReceipt,
         icp_to_evm_fee: Erc20TokenAmount,
+        finalized_at: u64,
     ) {
         if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            if tx.status != IcpToEvmStatus::PendingVerification
+                && tx.status.stage() < IcpToEvmStatus::Successful.stage()
+            {
+                let latency = finalized_at.saturating_sub(tx.time);
+                self.record_closed_transfer(
+                    TransactionKey::IcpToEvm(identifier.clone()),
+                    BridgeDirection::IcpToEvm,
+                    tx.operator,
+                    Erc20Identifier(tx.erc20_contract_address, tx.chain_id),
+                    tx.time,
+                    |stats| match receipt.status {
+                        TransactionStatus::Success => stats.record_completed(latency),
+                        TransactionStatus::Failure => stats.record_failed(),
+                    },
+                );
+            }
+
             let gas_used = nat_to_erc20_amount(receipt.gas_used);
             let effective_gas_price = nat_to_erc20_amount(receipt.effective_gas_price);
 
@@ -671,23 +1555,53 @@ impl State {
                 TransactionStatus::Success => IcpToEvmStatus::Successful,
                 TransactionStatus::Failure => IcpToEvmStatus::Failed,
             };
+
+            self.icp_to_evm_hash_index.insert(
+                TxHashKey(receipt.transaction_hash.clone(), tx.chain_id),
+                identifier.clone(),
+            );
+
             let new_tx = IcpToEvmTx {
                 actual_received,
                 transaction_hash: Some(receipt.transaction_hash),
                 gas_used: Some(gas_used),
                 effective_gas_price: Some(effective_gas_price),
                 total_gas_spent: Some(total_gas_spent),
-                status,
+                status: tx.status.advance_to(status),
                 ..tx
             };
             self.record_new_icp_to_evm(identifier, new_tx);
         }
     }
 
-    pub fn record_reimbursed_icp_to_evm(&mut self, identifier: IcpToEvmIdentifier) {
+    // Reimbursements without a ledger id are made on the native ledger of the chain
+    pub fn record_reimbursed_icp_to_evm(
+        &mut self,
+        identifier: IcpToEvmIdentifier,
+        reimbursement: Reimbursement,
+    ) {
         if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            let ledger_id = reimbursement.ledger_id.or_else(|| {
+                self.get_icrc_twin_for_erc20(
+                    &Erc20Identifier::new(
+                        &Address::from_str(NATIVE_ERC20_ADDRESS)
+                            .expect("Should not fail converintg to address"),
+                        tx.chain_id,
+                    ),
+                    &tx.operator,
+                )
+            });
+
+            self.record_reimbursed_icp_to_evm_stats(&identifier, &tx);
+            self.reimbursements.insert(
+                identifier.clone(),
+                Reimbursement {
+                    ledger_id,
+                    ..reimbursement
+                },
+            );
             let new_tx = IcpToEvmTx {
-                status: IcpToEvmStatus::Reimbursed,
+                status: tx.status.advance_to(IcpToEvmStatus::Reimbursed),
                 ..tx
             };
             self.record_new_icp_to_evm(identifier, new_tx);
@@ -696,14 +1610,126 @@ impl State {
 
     pub fn record_quarantined_reimbursed_icp_to_evm(&mut self, identifier: IcpToEvmIdentifier) {
         if let Some(tx) = self.icp_to_evm_txs.get(&identifier) {
+            self.record_reimbursed_icp_to_evm_stats(&identifier, &tx);
             let new_tx = IcpToEvmTx {
-                status: IcpToEvmStatus::QuarantinedReimbursement,
+                status: tx
+                    .status
+                    .advance_to(IcpToEvmStatus::QuarantinedReimbursement),
                 ..tx
             };
             self.record_new_icp_to_evm(identifier, new_tx);
         }
     }
 
+    // Reimbursed withdrawals count as failed, unless their failed transaction was already counted
+    fn record_reimbursed_icp_to_evm_stats(
+        &mut self,
+        identifier: &IcpToEvmIdentifier,
+        tx: &IcpToEvmTx,
+    ) {
+        if !matches!(
+            tx.status,
+            IcpToEvmStatus::PendingVerification
+                | IcpToEvmStatus::Failed
+                | IcpToEvmStatus::Reimbursed
+                | IcpToEvmStatus::QuarantinedReimbursement
+        ) {
+            self.record_closed_transfer(
+                TransactionKey::IcpToEvm(identifier.clone()),
+                BridgeDirection::IcpToEvm,
+                tx.operator,
+                Erc20Identifier(tx.erc20_contract_address, tx.chain_id),
+                tx.time,
+                TransferStats::record_failed,
+            );
+        }
+    }
+
+    // Counts a newly accepted transfer and snapshots its usd value with the current price of its
+    // twin token, so historical volume does not move with later prices
+    fn record_accepted_transfer(
+        &mut self,
+        key: TransactionKey,
+        direction: BridgeDirection,
+        stats_key: BridgeStatsKey,
+        icrc_ledger_id: Option<Principal>,
+        amount: Erc20TokenAmount,
+    ) {
+        let mut bucket = self.bridge_stats.get(&stats_key).unwrap_or_default();
+        bucket.direction_mut(direction).record_accepted(amount);
+        self.bridge_stats.insert(stats_key.clone(), bucket);
+        self.open_transfers.insert(key.clone(), ());
+
+        let BridgeStatsKey(_day, operator, token) = &stats_key;
+        let Some(usd_value) = self
+            .get_evm_token_by_identifier(token)
+            .zip(icrc_ledger_id.or_else(|| self.get_icrc_twin_for_erc20(token, operator)))
+            .and_then(|(evm_token, ledger_id)| {
+                TransferUsdValue::new(
+                    amount,
+                    evm_token.decimals,
+                    self.get_icp_token_price(&ledger_id)?,
+                )
+            })
+        else {
+            return;
+        };
+
+        let mut volume = self.usd_volumes.get(&stats_key).unwrap_or_default();
+        volume.direction_mut(direction).record(usd_value.usd_value);
+        self.usd_volumes.insert(stats_key, volume);
+        self.transfer_usd_values.insert(key, usd_value);
+    }
+
+    pub fn get_transfer_usd_value(&self, key: &TransactionKey) -> Option<TransferUsdValue> {
+        self.transfer_usd_values.get(key)
+    }
+
+    // Daily usd volumes of every operator and token for the days overlapping the range
+    pub fn get_usd_volumes(&self, from: u64, to: u64) -> Vec<CandidDailyUsdVolume> {
+        let start = BridgeStatsKey(
+            day_of(from),
+            Operator::DfinityCkEthMinter,
+            Erc20Identifier(Address::ZERO, ChainId(0)),
+        );
+        self.usd_volumes
+            .range(start..)
+            .take_while(|(BridgeStatsKey(day, _operator, _token), _bucket)| *day <= day_of(to))
+            .map(CandidDailyUsdVolume::from)
+            .collect()
+    }
+
+    // Counts the completion or failure of a transfer in the bucket of the day it was accepted
+    // Transfers that were not counted as accepted, like the ones accepted before bridge stats
+    // were recorded, are skipped so a bucket never has more outcomes than transfers
+    fn record_closed_transfer(
+        &mut self,
+        key: TransactionKey,
+        direction: BridgeDirection,
+        operator: Operator,
+        token: Erc20Identifier,
+        accepted_at: u64,
+        update: impl FnOnce(&mut TransferStats),
+    ) {
+        if self.open_transfers.remove(&key).is_none() {
+            return;
+        }
+        let stats_key = BridgeStatsKey::new(accepted_at, operator, token);
+        let mut bucket = self.bridge_stats.get(&stats_key).unwrap_or_default();
+        update(bucket.direction_mut(direction));
+        self.bridge_stats.insert(stats_key, bucket);
+    }
+
+    pub fn get_bridge_stats(&self, window: StatsWindow, now: u64) -> Vec<CandidBridgeStats> {
+        let first_day = window.first_day(now).unwrap_or(0);
+        let start = BridgeStatsKey(
+            first_day,
+            Operator::DfinityCkEthMinter,
+            Erc20Identifier(Address::ZERO, ChainId(0)),
+        );
+        aggregate_bridge_stats(self.bridge_stats.range(start..))
+    }
+
     pub fn all_unverified_icp_to_evm(&self) -> Vec<(IcpToEvmIdentifier, u64)> {
         self.icp_to_evm_txs
             .iter()
@@ -713,7 +1739,23 @@ impl State {
     }
 
     pub fn remove_unverified_icp_to_evm(&mut self, identifier: &IcpToEvmIdentifier) {
-        self.icp_to_evm_txs.remove(identifier);
+        if let Some(tx) = self.icp_to_evm_txs.remove(identifier) {
+            self.update_status_counts(
+                &TransactionKey::IcpToEvm(identifier.clone()),
+                Some(TimelineStatus::IcpToEvm(tx.status.clone())),
+                None,
+            );
+            if let Some(transaction_hash) = tx.transaction_hash {
+                self.icp_to_evm_hash_index
+                    .remove(&TxHashKey(transaction_hash, tx.chain_id));
+            }
+            self.unindex_transaction(
+                TransactionKey::IcpToEvm(identifier.clone()),
+                tx.destination,
+                tx.from,
+                tx.time,
+            );
+        }
     }
 
     pub fn all_unverified_evm_to_icp(&self) -> Vec<(EvmToIcpTxIdentifier, u64)> {
@@ -725,84 +1767,379 @@ impl State {
     }
 
     pub fn remove_unverified_evm_to_icp(&mut self, identifier: &EvmToIcpTxIdentifier) {
-        self.evm_to_icp_txs.remove(identifier);
+        if let Some(tx) = self.evm_to_icp_txs.remove(identifier) {
+            self.update_status_counts(
+                &TransactionKey::EvmToIcp(identifier.clone()),
+                Some(TimelineStatus::EvmToIcp(tx.status.clone())),
+                None,
+            );
+            if let Some(ledger_mint_index) = tx.ledger_mint_index {
+                self.evm_to_icp_mint_index
+                    .remove(&MintIndexKey(ledger_mint_index, tx.chain_id));
+            }
+            self.unindex_transaction(
+                TransactionKey::EvmToIcp(identifier.clone()),
+                tx.from_address,
+                tx.principal,
+                tx.time,
+            );
+        }
     }
 
-    // Gets all the transaction history for an evm address
-    pub fn get_transaction_for_address(&self, address: Address) -> Vec<Transaction> {
-        let result: Vec<Transaction> = self
-            .evm_to_icp_txs
-            .iter()
-            .filter(|(_id, tx)| tx.from_address == address)
-            .map(|(_id, tx)| Transaction::from(CandidEvmToIcp::from(tx)))
+    // Moves a transaction from its previous status count to its current one
+    // Transactions a pending index backfill has not reached yet are counted by the backfill
+    fn update_status_counts(
+        &mut self,
+        key: &TransactionKey,
+        previous: Option<TimelineStatus>,
+        current: Option<TimelineStatus>,
+    ) {
+        let (previous, current) = (
+            previous.map(TimelineStatus::counted),
+            current.map(TimelineStatus::counted),
+        );
+        if previous == current || !self.is_backfilled(key) {
+            return;
+        }
+        if let Some(previous) = previous {
+            match self.transaction_status_counts.get(&previous) {
+                Some(count) if count > 1 => {
+                    self.transaction_status_counts.insert(previous, count - 1);
+                }
+                _ => {
+                    self.transaction_status_counts.remove(&previous);
+                }
+            }
+        }
+        if let Some(current) = current {
+            self.increment_status_count(current);
+        }
+    }
+
+    fn increment_status_count(&mut self, status: TimelineStatus) {
+        let status = status.counted();
+        let count = self.transaction_status_counts.get(&status).unwrap_or(0);
+        self.transaction_status_counts.insert(status, count + 1);
+    }
+
+    // False for transactions a pending index backfill has not reached yet
+    fn is_backfilled(&self, key: &TransactionKey) -> bool {
+        self.pending_index_backfill
+            .get(&())
+            .map_or(true, |next| key < &next)
+    }
+
+    fn index_transaction(
+        &mut self,
+        key: TransactionKey,
+        address: Address,
+        principal: Principal,
+        time: u64,
+    ) {
+        let cursor = TransactionCursor::new(time, key);
+        self.address_tx_index
+            .insert(TxIndexKey::new(address, cursor.clone()), ());
+        self.principal_tx_index
+            .insert(TxIndexKey::new(principal, cursor.clone()), ());
+        self.transaction_time_index.insert(cursor, ());
+    }
+
+    fn unindex_transaction(
+        &mut self,
+        key: TransactionKey,
+        address: Address,
+        principal: Principal,
+        time: u64,
+    ) {
+        let cursor = TransactionCursor::new(time, key);
+        self.address_tx_index
+            .remove(&TxIndexKey::new(address, cursor.clone()));
+        self.principal_tx_index
+            .remove(&TxIndexKey::new(principal, cursor.clone()));
+        self.transaction_time_index.remove(&cursor);
+    }
+
+    // Starts indexing the transactions recorded before the secondary and reverse indexes existed
+    // Nothing is started if the indexes are already populated or a backfill is in progress
+    pub fn start_index_backfill(&mut self) {
+        if self.address_tx_index.is_empty()
+            && self.pending_index_backfill.is_empty()
+            && !(self.evm_to_icp_txs.is_empty() && self.icp_to_evm_txs.is_empty())
+        {
+            self.pending_index_backfill.insert(
+                (),
+                TransactionKey::EvmToIcp(EvmToIcpTxIdentifier(String::new(), ChainId(0))),
+            );
+        }
+    }
+
+    pub fn is_index_backfill_pending(&self) -> bool {
+        !self.pending_index_backfill.is_empty()
+    }
+
+    // Indexes the next max_transactions transactions of a pending backfill, returns true if
+    // transactions are left to index
+    // Index entries are derived from the stored transaction, so a transaction recorded while the
+    // backfill runs is not indexed twice, status counts skip transactions the backfill has not
+    // reached yet
+    pub fn index_existing_transactions(&mut self, max_transactions: usize) -> bool {
+        let Some(next) = self.pending_index_backfill.get(&()) else {
+            return false;
+        };
+        let max_transactions = max_transactions.max(1);
+
+        let (evm_to_icp_start, icp_to_evm_start) = match next {
+            TransactionKey::EvmToIcp(identifier) => (
+                Some(identifier),
+                IcpToEvmIdentifier(LedgerBurnIndex::new(0), ChainId(0)),
+            ),
+            TransactionKey::IcpToEvm(identifier) => (None, identifier),
+        };
+        let mut keys: Vec<TransactionKey> = evm_to_icp_start
+            .into_iter()
+            .flat_map(|start| {
+                self.evm_to_icp_txs
+                    .range(start..)
+                    .map(|(identifier, _tx)| TransactionKey::EvmToIcp(identifier))
+            })
             .chain(
                 self.icp_to_evm_txs
-                    .iter()
-                    .filter(|(_id, tx)| tx.destination == address)
-                    .map(|(_id, tx)| Transaction::from(CandidIcpToEvm::from(tx))),
+                    .range(icp_to_evm_start..)
+                    .map(|(identifier, _tx)| TransactionKey::IcpToEvm(identifier)),
             )
+            .take(max_transactions.saturating_add(1))
             .collect();
 
-        result
+        let next = match keys.len() > max_transactions {
+            true => keys.pop(),
+            false => None,
+        };
+        for key in keys {
+            self.index_stored_transaction(key);
+        }
+
+        match next {
+            Some(next) => {
+                self.pending_index_backfill.insert((), next);
+                true
+            }
+            None => {
+                self.pending_index_backfill.remove(&());
+                false
+            }
+        }
+    }
+
+    fn index_stored_transaction(&mut self, key: TransactionKey) {
+        match &key {
+            TransactionKey::EvmToIcp(identifier) => {
+                if let Some(tx) = self.evm_to_icp_txs.get(identifier) {
+                    if let Some(ledger_mint_index) = tx.ledger_mint_index {
+                        self.evm_to_icp_mint_index.insert(
+                            MintIndexKey(ledger_mint_index, tx.chain_id),
+                            identifier.clone(),
+                        );
+                    }
+                    self.increment_status_count(TimelineStatus::EvmToIcp(tx.status.clone()));
+                    self.index_transaction(key.clone(), tx.from_address, tx.principal, tx.time);
+                }
+            }
+            TransactionKey::IcpToEvm(identifier) => {
+                if let Some(tx) = self.icp_to_evm_txs.get(identifier) {
+                    if let Some(transaction_hash) = tx.transaction_hash {
+                        self.icp_to_evm_hash_index
+                            .insert(TxHashKey(transaction_hash, tx.chain_id), identifier.clone());
+                    }
+                    self.increment_status_count(TimelineStatus::IcpToEvm(tx.status.clone()));
+                    self.index_transaction(key.clone(), tx.destination, tx.from, tx.time);
+                }
+            }
+        }
+    }
+
+    fn candid_icp_to_evm(&self, identifier: &IcpToEvmIdentifier, tx: IcpToEvmTx) -> Transaction {
+        Transaction::from(CandidIcpToEvm::from((
+            tx,
+            self.reimbursements.get(identifier),
+        )))
+    }
+
+    fn get_transaction_by_key(&self, key: &TransactionKey) -> Option<Transaction> {
+        match key {
+            TransactionKey::EvmToIcp(identifier) => self
+                .evm_to_icp_txs
+                .get(identifier)
+                .map(|tx| Transaction::from(CandidEvmToIcp::from(tx))),
+            TransactionKey::IcpToEvm(identifier) => self
+                .icp_to_evm_txs
+                .get(identifier)
+                .map(|tx| self.candid_icp_to_evm(identifier, tx)),
+        }
+    }
+
+    // Gets all the transaction history for an evm address, newest first
+    pub fn get_transaction_for_address(&self, address: Address) -> Vec<Transaction> {
+        self.get_transactions_page_for_address(address, None, u64::MAX)
+            .transactions
     }
 
-    // Gets all the transaction history for a principal
+    // Gets all the transaction history for a principal, newest first
     pub fn get_transaction_for_principal(&self, principal_id: Principal) -> Vec<Transaction> {
-        let result: Vec<Transaction> = self
-            .evm_to_icp_txs
+        self.get_transactions_page_for_principal(principal_id, None, u64::MAX)
+            .transactions
+    }
+
+    // Gets a single page of the transaction history for an evm address, newest first
+    pub fn get_transactions_page_for_address(
+        &self,
+        address: Address,
+        cursor: Option<TransactionCursor>,
+        limit: u64,
+    ) -> TransactionsPage {
+        let page = read_tx_index(&self.address_tx_index, address, cursor, limit);
+        self.load_transactions_page(page)
+    }
+
+    // Gets a single page of the transaction history for a principal, newest first
+    pub fn get_transactions_page_for_principal(
+        &self,
+        principal_id: Principal,
+        cursor: Option<TransactionCursor>,
+        limit: u64,
+    ) -> TransactionsPage {
+        let page = read_tx_index(&self.principal_tx_index, principal_id, cursor, limit);
+        self.load_transactions_page(page)
+    }
+
+    // Gets the latest transactions across all minters, newest first
+    pub fn get_recent_transactions(&self, limit: usize) -> Vec<Transaction> {
+        self.transaction_time_index
             .iter()
-            .filter(|(_id, tx)| tx.principal == principal_id)
-            .map(|(_id, tx)| Transaction::from(CandidEvmToIcp::from(tx)))
-            .chain(
-                self.icp_to_evm_txs
-                    .iter()
-                    .filter(|(_id, tx)| tx.from == principal_id)
-                    .map(|(_id, tx)| Transaction::from(CandidIcpToEvm::from(tx))),
-            )
-            .collect();
+            .take(limit)
+            .filter_map(|(cursor, ())| self.get_transaction_by_key(&cursor.key))
+            .collect()
+    }
 
-        result
+    fn load_transactions_page(&self, page: TxIndexPage) -> TransactionsPage {
+        TransactionsPage {
+            transactions: page
+                .keys
+                .iter()
+                .filter_map(|key| self.get_transaction_by_key(key))
+                .collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+            total_count: page.total_count,
+        }
     }
 
     // Gets supported twin token pairs for both Appic and Dfinity NNS Twin tokens
+    // Pairs of hidden or deleted evm tokens are left out
     pub fn get_suported_bridge_pairs(&self) -> Vec<TokenPair> {
         self.supported_ckerc20_tokens
-            .values()
-            .filter_map(|bridge_pair| {
-                // Update usd price
-                let icp_token_with_new_usd_price = IcpToken {
-                    usd_price: self
-                        .get_icp_token_price(&bridge_pair.icp_token.ledger_id)
-                        .unwrap_or("0".to_string()),
-                    ..bridge_pair.icp_token
-                };
-                Some(TokenPair {
-                    evm_token: CandidEvmToken::from(bridge_pair.evm_token),
-                    icp_token: CandidIcpToken::from(icp_token_with_new_usd_price),
-                    operator: Operator::DfinityCkEthMinter,
-                })
+            .iter()
+            .map(|(identifier, bridge_pair)| {
+                (identifier, bridge_pair, Operator::DfinityCkEthMinter)
             })
             .chain(
                 self.supported_twin_appic_tokens
-                    .values()
-                    .filter_map(|bridge_pair| {
-                        // Update usd price
-                        let icp_token_with_new_usd_price = IcpToken {
-                            usd_price: self
-                                .get_icp_token_price(&bridge_pair.icp_token.ledger_id)
-                                .unwrap_or("0".to_string()),
-                            ..bridge_pair.icp_token
-                        };
-                        Some(TokenPair {
-                            evm_token: CandidEvmToken::from(bridge_pair.evm_token),
-                            icp_token: CandidIcpToken::from(icp_token_with_new_usd_price),
-                            operator: Operator::AppicMinter,
-                        })
+                    .iter()
+                    .map(|(identifier, bridge_pair)| {
+                        (identifier, bridge_pair, Operator::AppicMinter)
                     }),
             )
+            .filter(|(identifier, _bridge_pair, _operator)| self.is_evm_token_listed(identifier))
+            .map(|(_identifier, bridge_pair, operator)| TokenPair {
+                evm_token: self.get_candid_evm_token(bridge_pair.evm_token),
+                icp_token: CandidIcpToken::from(self.current_icp_token(bridge_pair.icp_token)),
+                operator,
+            })
             .collect()
     }
 
+    // Latest metadata and usd price of the icp token of a bridge pair, pairs keep the token as it
+    // was when they were recorded
+    fn current_icp_token(&self, icp_token: IcpToken) -> IcpToken {
+        self.get_icp_token_by_principal(&icp_token.ledger_id)
+            .unwrap_or(IcpToken {
+                usd_price: UsdPrice::ZERO,
+                ..icp_token
+            })
+    }
+
+    // Records the bridge pair of a twin token announced by a minter event, so the pair is usable
+    // before update_bridge_pairs runs
+    // The side of the pair that is not known yet is derived from the other one, None if the pair
+    // already exists, the evm token was deleted through the registry, neither side is known, or
+    // the icp token is derived but the fee of its ledger is not given, so no pair is published
+    // with a wrong fee
+    pub fn record_added_twin_token(
+        &mut self,
+        operator: Operator,
+        identifier: Erc20Identifier,
+        twin_symbol: &str,
+        ledger_id: Principal,
+        ledger_fee: Option<Erc20TokenAmount>,
+    ) -> Option<BridgePair> {
+        if self
+            .get_icrc_twin_for_erc20(&identifier, &operator)
+            .is_some()
+        {
+            return None;
+        }
+        if self.evm_token_edits.get(&identifier) == Some(EvmTokenEdit::Deleted) {
+            return None;
+        }
+
+        let request = self.twin_erc20_requests.get(&identifier);
+        let evm_token = self.get_evm_token_by_identifier(&identifier).or_else(|| {
+            request
+                .as_ref()
+                .and_then(|request| request.evm_token.clone())
+        });
+        let icp_token = self.get_icp_token_by_principal(&ledger_id).or_else(|| {
+            request
+                .and_then(|request| request.icp_token)
+                .filter(|icp_token| icp_token.ledger_id == ledger_id)
+        });
+
+        let (evm_token, icp_token) = match (evm_token, icp_token) {
+            (Some(evm_token), Some(icp_token)) => (evm_token, icp_token),
+            (Some(evm_token), None) => {
+                let icp_token = IcpToken::twin_of(&evm_token, ledger_id, twin_symbol, ledger_fee?);
+                (evm_token, icp_token)
+            }
+            (None, Some(icp_token)) => {
+                let evm_token = EvmToken::twin_of(&icp_token, &identifier, twin_symbol);
+                (evm_token, icp_token)
+            }
+            (None, None) => return None,
+        };
+
+        // Tokens deleted through the registry are not added back
+        if self.evm_token_list.get(&identifier).is_none()
+            && self.evm_token_edits.get(&identifier).is_none()
+        {
+            self.record_evm_token(identifier.clone(), evm_token.clone());
+        }
+        if self.icp_token_list.get(&ledger_id).is_none() {
+            self.record_icp_token(ledger_id, icp_token.clone());
+        }
+
+        let bridge_pair = BridgePair {
+            icp_token,
+            evm_token,
+        };
+        match operator {
+            Operator::DfinityCkEthMinter => self
+                .supported_ckerc20_tokens
+                .insert(identifier, bridge_pair.clone()),
+            Operator::AppicMinter => self
+                .supported_twin_appic_tokens
+                .insert(identifier, bridge_pair.clone()),
+        };
+        Some(bridge_pair)
+    }
+
     // Searches for a transaction by hash in both evm_to_icp and icp_to_evm
     fn get_transaction_by_hash(&self, tx_hash: &String, chain_id: ChainId) -> Option<Transaction> {
         let evm_to_icp_id = EvmToIcpTxIdentifier::new(tx_hash, chain_id);
@@ -811,12 +2148,13 @@ impl State {
             .get(&evm_to_icp_id)
             .map(|tx| Transaction::from(CandidEvmToIcp::from(tx)))
             .or_else(|| {
-                self.icp_to_evm_txs
-                    .values()
-                    .find(|tx| {
-                        tx.chain_id == chain_id && tx.transaction_hash.as_ref() == Some(tx_hash)
+                self.icp_to_evm_hash_index
+                    .get(&TxHashKey(tx_hash.clone(), chain_id))
+                    .and_then(|identifier| {
+                        self.icp_to_evm_txs
+                            .get(&identifier)
+                            .map(|tx| self.candid_icp_to_evm(&identifier, tx))
                     })
-                    .map(|tx| Transaction::from(CandidIcpToEvm::from(tx)))
             })
     }
 
@@ -830,7 +2168,7 @@ impl State {
 
         self.icp_to_evm_txs
             .get(&icp_to_evm_id)
-            .map(|tx| Transaction::from(CandidIcpToEvm::from(tx)))
+            .map(|tx| self.candid_icp_to_evm(&icp_to_evm_id, tx))
     }
 
     // Searches for a transaction by mint id in evm_to_icp_txs
@@ -839,12 +2177,147 @@ impl State {
         ledger_mint_index: LedgerMintIndex,
         chain_id: ChainId,
     ) -> Option<Transaction> {
-        self.evm_to_icp_txs
-            .values()
-            .find(|tx| tx.chain_id == chain_id && tx.ledger_mint_index == Some(ledger_mint_index))
+        self.evm_to_icp_mint_index
+            .get(&MintIndexKey(ledger_mint_index, chain_id))
+            .and_then(|identifier| self.evm_to_icp_txs.get(&identifier))
             .map(|tx| Transaction::EvmToIcp(CandidEvmToIcp::from(tx)))
     }
 
+    // Key of the transaction matching the search param, if there is one
+    pub fn get_transaction_key_by_search_params(
+        &self,
+        search_param: TransactionSearchParam,
+        chain_id: ChainId,
+    ) -> Option<TransactionKey> {
+        match search_param {
+            TransactionSearchParam::TxHash(tx_hash) => {
+                let evm_to_icp_id = EvmToIcpTxIdentifier::new(&tx_hash, chain_id);
+                if self.evm_to_icp_txs.contains_key(&evm_to_icp_id) {
+                    return Some(TransactionKey::EvmToIcp(evm_to_icp_id));
+                }
+                self.icp_to_evm_hash_index
+                    .get(&TxHashKey(tx_hash, chain_id))
+                    .map(TransactionKey::IcpToEvm)
+            }
+
+            TransactionSearchParam::TxWithdrawalId(withdrawal_id) => {
+                let icp_to_evm_id =
+                    IcpToEvmIdentifier(nat_to_ledger_burn_index(&withdrawal_id), chain_id);
+                self.icp_to_evm_txs
+                    .contains_key(&icp_to_evm_id)
+                    .then_some(TransactionKey::IcpToEvm(icp_to_evm_id))
+            }
+
+            TransactionSearchParam::TxMintId(mint_id) => self
+                .evm_to_icp_mint_index
+                .get(&MintIndexKey(nat_to_ledger_mint_index(&mint_id), chain_id))
+                .map(TransactionKey::EvmToIcp),
+        }
+    }
+
+    // Records the status a transaction moved to when the minter event at event_index was applied
+    // Replaying the same event overwrites its entry, so timelines stay free of duplicates
+    pub fn record_timeline_entry(
+        &mut self,
+        key: TransactionKey,
+        event_index: u64,
+        status: TimelineStatus,
+        timestamp: u64,
+    ) {
+        let exists = match &key {
+            TransactionKey::EvmToIcp(identifier) => self.evm_to_icp_txs.contains_key(identifier),
+            TransactionKey::IcpToEvm(identifier) => self.icp_to_evm_txs.contains_key(identifier),
+        };
+
+        if exists {
+            self.transaction_timelines.insert(
+                TimelineKey(key, event_index),
+                TimelineEntry { status, timestamp },
+            );
+        }
+    }
+
+    // Timeline of a transaction ordered by minter event index
+    pub fn get_transaction_timeline(&self, key: &TransactionKey) -> Vec<(u64, TimelineEntry)> {
+        self.transaction_timelines
+            .range(TimelineKey(key.clone(), 0)..=TimelineKey(key.clone(), u64::MAX))
+            .map(|(TimelineKey(_key, event_index), entry)| (event_index, entry))
+            .collect()
+    }
+
+    // Time the minter accepted a deposit, taken from its timeline
+    pub fn get_deposit_accepted_time(&self, identifier: &EvmToIcpTxIdentifier) -> Option<u64> {
+        self.get_transaction_timeline(&TransactionKey::EvmToIcp(identifier.clone()))
+            .into_iter()
+            .find(|(_event_index, entry)| {
+                entry.status == TimelineStatus::EvmToIcp(EvmToIcpStatus::Accepted)
+            })
+            .map(|(_event_index, entry)| entry.timestamp)
+    }
+
+    // Records the latency of a deposit that is about to be minted
+    // Deposits that are already minted are skipped, so replayed events are not counted twice
+    fn record_deposit_latency(&mut self, identifier: &EvmToIcpTxIdentifier, minted_at: u64) {
+        let Some(tx) = self.evm_to_icp_txs.get(identifier) else {
+            return;
+        };
+        if tx.status == EvmToIcpStatus::Minted {
+            return;
+        }
+        let Some(latency) = self
+            .get_deposit_accepted_time(identifier)
+            .and_then(|accepted_at| minted_at.checked_sub(accepted_at))
+        else {
+            return;
+        };
+
+        let minter_key = MinterKey(tx.chain_id, tx.operator);
+        let mut latencies = self.get_deposit_latencies(&minter_key);
+        latencies.samples.push(latency);
+        if latencies.samples.len() > MAX_DEPOSIT_LATENCY_SAMPLES {
+            latencies.samples.remove(0);
+        }
+        self.deposit_latencies.insert(minter_key, latencies);
+    }
+
+    pub fn get_deposit_latencies(&self, minter_key: &MinterKey) -> DepositLatencies {
+        self.deposit_latencies.get(minter_key).unwrap_or_default()
+    }
+
+    // Estimates when a deposit that is not minted yet will be, from the median latency of its minter
+    // Deposits the minter has not accepted yet are estimated from the time they were submitted
+    pub fn estimate_deposit_mint_time(
+        &self,
+        identifier: &EvmToIcpTxIdentifier,
+    ) -> Option<CandidDepositEta> {
+        let tx = self.evm_to_icp_txs.get(identifier)?;
+        if !matches!(
+            tx.status,
+            EvmToIcpStatus::PendingVerification | EvmToIcpStatus::Accepted
+        ) {
+            return None;
+        }
+
+        let minter_key = MinterKey(tx.chain_id, tx.operator);
+        let latencies = self.get_deposit_latencies(&minter_key);
+        let median_latency = latencies.median();
+        let started_at = self
+            .get_deposit_accepted_time(identifier)
+            .unwrap_or(tx.time);
+
+        Some(CandidDepositEta {
+            status: tx.status,
+            block_number: tx.block_number.map(Nat::from),
+            minter_synced_block: self
+                .get_synced_blocks(&minter_key)
+                .deposits_synced_to(&tx.erc20_contract_address)
+                .map(Nat::from),
+            latency_samples: latencies.samples.len() as u64,
+            median_latency_seconds: median_latency.map(|latency| latency / 1_000_000_000),
+            estimated_mint_time: median_latency.map(|latency| started_at.saturating_add(latency)),
+        })
+    }
+
     // Gets a single transaction by search param
     // Returns none if no transaction is available
     pub fn get_transaction_by_search_params(
@@ -881,6 +2354,64 @@ impl State {
         });
     }
 
+    // Adds or updates a token through the registry, hidden tokens stay hidden
+    pub fn upsert_evm_token(&mut self, token: EvmToken) {
+        let identifier = Erc20Identifier::from(&token);
+        if self.evm_token_edits.get(&identifier) != Some(EvmTokenEdit::Hidden) {
+            self.evm_token_edits
+                .insert(identifier.clone(), EvmTokenEdit::Upserted);
+        }
+        self.evm_token_list.insert(identifier, token);
+    }
+
+    // Returns false if the token does not exist
+    pub fn set_evm_token_hidden(&mut self, identifier: &Erc20Identifier, hidden: bool) -> bool {
+        if self.evm_token_list.get(identifier).is_none() {
+            return false;
+        }
+        let edit = match hidden {
+            true => EvmTokenEdit::Hidden,
+            false => EvmTokenEdit::Upserted,
+        };
+        self.evm_token_edits.insert(identifier.clone(), edit);
+        true
+    }
+
+    pub fn is_evm_token_hidden(&self, identifier: &Erc20Identifier) -> bool {
+        self.evm_token_edits.get(identifier) == Some(EvmTokenEdit::Hidden)
+    }
+
+    // False if the token was hidden or deleted through the registry
+    pub fn is_evm_token_listed(&self, identifier: &Erc20Identifier) -> bool {
+        !matches!(
+            self.evm_token_edits.get(identifier),
+            Some(EvmTokenEdit::Hidden | EvmTokenEdit::Deleted)
+        )
+    }
+
+    // Bridge pairs of the token are kept but left out of get_bridge_pairs
+    pub fn delete_evm_token(&mut self, identifier: &Erc20Identifier) -> Option<EvmToken> {
+        let token = self.evm_token_list.remove(identifier)?;
+        self.evm_token_edits
+            .insert(identifier.clone(), EvmTokenEdit::Deleted);
+        self.evm_token_prices.remove(identifier);
+        Some(token)
+    }
+
+    // Records the bundled tokens that were not edited at runtime, returns the number of tokens
+    // recorded
+    pub fn merge_bundled_evm_tokens(&mut self, tokens: Vec<EvmToken>) -> usize {
+        let mut merged = 0;
+        for token in tokens {
+            let identifier = Erc20Identifier::from(&token);
+            if self.evm_token_edits.get(&identifier).is_none() {
+                self.evm_token_list.insert(identifier, token);
+                merged += 1;
+            }
+        }
+        merged
+    }
+
     // Records a single icp token
     pub fn record_icp_token(&mut self, ledger_id: Principal, token: IcpToken) {
         self.icp_token_list.insert(ledger_id, token);
@@ -897,6 +2428,50 @@ impl State {
         self.evm_token_list.get(identifier)
     }
 
+    // Price of the icp twin of the token if it has a non zero one, the controller pushed price
+    // otherwise
+    pub fn get_evm_token_price(&self, identifier: &Erc20Identifier) -> Option<UsdPrice> {
+        self.supported_ckerc20_tokens
+            .get(identifier)
+            .into_iter()
+            .chain(self.supported_twin_appic_tokens.get(identifier))
+            .filter_map(|bridge_pair| self.get_icp_token_price(&bridge_pair.icp_token.ledger_id))
+            .find(|usd_price| *usd_price != UsdPrice::ZERO)
+            .or_else(|| {
+                self.evm_token_prices
+                    .get(identifier)
+                    .map(|price| price.usd_price)
+            })
+    }
+
+    // None removes the pushed price of the token
+    pub fn set_evm_token_price(
+        &mut self,
+        identifier: Erc20Identifier,
+        usd_price: Option<UsdPrice>,
+        updated_at: u64,
+    ) {
+        match usd_price {
+            Some(usd_price) => {
+                self.evm_token_prices.insert(
+                    identifier,
+                    EvmTokenPrice {
+                        usd_price,
+                        updated_at,
+                    },
+                );
+            }
+            None => {
+                self.evm_token_prices.remove(&identifier);
+            }
+        }
+    }
+
+    pub fn get_candid_evm_token(&self, token: EvmToken) -> CandidEvmToken {
+        let usd_price = self.get_evm_token_price(&Erc20Identifier::from(&token));
+        CandidEvmToken::from((token, usd_price))
+    }
+
     pub fn get_icp_token_by_principal(&self, ledger_id: &Principal) -> Option<IcpToken> {
         self.icp_token_list.get(ledger_id)
     }
@@ -905,7 +2480,7 @@ impl State {
         self.icp_token_list.values().collect()
     }
 
-    pub fn get_icp_token_price(&self, ledger_id: &Principal) -> Option<String> {
+    pub fn get_icp_token_price(&self, ledger_id: &Principal) -> Option<UsdPrice> {
         self.icp_token_list
             .get(ledger_id)
             .map(|token| token.usd_price)
@@ -913,10 +2488,17 @@ impl State {
 
     pub fn remove_icp_token(&mut self, ledger_id: &Principal) {
         self.icp_token_list.remove(ledger_id);
+        self.remove_token_prices_before(*ledger_id, u64::MAX);
     }
 
-    pub fn update_icp_token_usd_price(&mut self, ledger_id: Principal, new_usd_price: String) {
+    pub fn update_icp_token_usd_price(
+        &mut self,
+        ledger_id: Principal,
+        new_usd_price: UsdPrice,
+        timestamp: u64,
+    ) {
         if let Some(token) = self.icp_token_list.get(&ledger_id) {
+            self.record_token_price_point(ledger_id, new_usd_price, timestamp);
             self.icp_token_list.insert(
                 ledger_id,
                 IcpToken {
@@ -927,23 +2509,166 @@ impl State {
         };
     }
 
-    pub fn get_erc20_ls_requests_by_principal(
-        &self,
-        principal: Principal,
-    ) -> Vec<Erc20TwinLedgerSuiteRequest> {
-        self.twin_erc20_requests
-            .iter()
-            .filter_map(|(_identifier, request)| {
-                if request.creator == principal {
-                    return Some(request);
-                } else {
-                    return None;
+    // Later prices of the same hour or day replace earlier ones
+    fn record_token_price_point(
+        &mut self,
+        ledger_id: Principal,
+        usd_price: UsdPrice,
+        timestamp: u64,
+    ) {
+        self.hourly_token_prices
+            .insert(TokenPriceKey::hourly(ledger_id, timestamp), usd_price);
+        self.daily_token_prices
+            .insert(TokenPriceKey::daily(ledger_id, timestamp), usd_price);
+
+        // Hourly points past their retention are covered by the daily point of their day
+        self.remove_hourly_token_prices_before(
+            ledger_id,
+            timestamp.saturating_sub(HOURLY_PRICE_RETENTION),
+        );
+        self.remove_daily_token_prices_before(
+            ledger_id,
+            timestamp.saturating_sub(DAILY_PRICE_RETENTION),
+        );
+    }
+
+    fn remove_token_prices_before(&mut self, ledger_id: Principal, timestamp: u64) {
+        self.remove_hourly_token_prices_before(ledger_id, timestamp);
+        self.remove_daily_token_prices_before(ledger_id, timestamp);
+    }
+
+    fn remove_hourly_token_prices_before(&mut self, ledger_id: Principal, timestamp: u64) {
+        let expired: Vec<TokenPriceKey> = self
+            .hourly_token_prices
+            .range(TokenPriceKey(ledger_id, 0)..TokenPriceKey(ledger_id, timestamp))
+            .map(|(key, _price)| key)
+            .collect();
+        for key in expired {
+            self.hourly_token_prices.remove(&key);
+        }
+    }
+
+    fn remove_daily_token_prices_before(&mut self, ledger_id: Principal, timestamp: u64) {
+        let expired: Vec<TokenPriceKey> = self
+            .daily_token_prices
+            .range(TokenPriceKey(ledger_id, 0)..TokenPriceKey(ledger_id, timestamp))
+            .map(|(key, _price)| key)
+            .collect();
+        for key in expired {
+            self.daily_token_prices.remove(&key);
+        }
+    }
+
+    // Price points of a token within the range, daily until the first hourly point and hourly after
+    pub fn get_token_price_history(
+        &self,
+        ledger_id: Principal,
+        from: u64,
+        to: u64,
+    ) -> Vec<CandidPricePoint> {
+        if from > to {
+            return vec![];
+        }
+
+        let hourly_since = self
+            .hourly_token_prices
+            .range(TokenPriceKey(ledger_id, 0)..=TokenPriceKey(ledger_id, u64::MAX))
+            .next()
+            .map(|(key, _price)| TokenPriceKey::daily(ledger_id, key.timestamp()).timestamp())
+            .unwrap_or(u64::MAX);
+
+        let daily = self
+            .daily_token_prices
+            .range(TokenPriceKey(ledger_id, from)..=TokenPriceKey(ledger_id, to))
+            .take_while(|(key, _price)| key.timestamp() < hourly_since);
+        let hourly = self
+            .hourly_token_prices
+            .range(TokenPriceKey(ledger_id, from.max(hourly_since))..)
+            .take_while(|(key, _price)| key.0 == ledger_id && key.timestamp() <= to);
+
+        daily.chain(hourly).map(CandidPricePoint::from).collect()
+    }
+
+    pub fn get_erc20_ls_requests_by_principal(
+        &self,
+        principal: Principal,
+    ) -> Vec<Erc20TwinLedgerSuiteRequest> {
+        self.twin_erc20_requests
+            .iter()
+            .filter_map(|(_identifier, request)| {
+                if request.creator == principal {
+                    return Some(request);
+                } else {
+                    return None;
                 }
             })
             .collect()
     }
 }
 
+// Maximum number of transactions returned in a single page
+pub const MAX_TRANSACTIONS_PER_PAGE: u64 = 100;
+
+// Number of existing transactions indexed per message while the indexes are backfilled
+pub const INDEX_BACKFILL_CHUNK_SIZE: usize = 1_000;
+
+// Number of latest deposits of a minter the mint time estimation is based on
+pub const MAX_DEPOSIT_LATENCY_SAMPLES: usize = 100;
+
+// Result of reading a single page from a secondary transaction index
+struct TxIndexPage {
+    keys: Vec<TransactionKey>,
+    next_cursor: Option<TransactionCursor>,
+    total_count: Option<u64>,
+}
+
+// Reads the transactions of an account that come right after the cursor
+// Unbounded histories are only read internally, callers of the paginated endpoints are capped
+// Counting the history is a scan of all its entries, so it is only done for the first page
+fn read_tx_index<Account>(
+    index: &BTreeMap<TxIndexKey<Account>, (), StableMemory>,
+    account: Account,
+    cursor: Option<TransactionCursor>,
+    limit: u64,
+) -> TxIndexPage
+where
+    Account: Clone + Ord + Serialize + for<'a> Deserialize<'a>,
+{
+    let limit = limit.max(1) as usize;
+
+    let total_count = cursor.is_none().then(|| {
+        index
+            .range(TxIndexKey::new(account.clone(), TransactionCursor::start())..)
+            .take_while(|(entry, _)| entry.account == account)
+            .count() as u64
+    });
+
+    let start = match cursor {
+        Some(cursor) => Bound::Excluded(TxIndexKey::new(account.clone(), cursor)),
+        None => Bound::Included(TxIndexKey::new(account.clone(), TransactionCursor::start())),
+    };
+
+    let mut cursors: Vec<TransactionCursor> = index
+        .range((start, Bound::Unbounded))
+        .take_while(|(entry, _)| entry.account == account)
+        .take(limit.saturating_add(1))
+        .map(|(entry, _)| entry.cursor)
+        .collect();
+
+    let next_cursor = if cursors.len() > limit {
+        cursors.truncate(limit);
+        cursors.last().cloned()
+    } else {
+        None
+    };
+
+    TxIndexPage {
+        keys: cursors.into_iter().map(|cursor| cursor.key).collect(),
+        next_cursor,
+        total_count,
+    }
+}
+
 pub fn is_native_token(address: &Address) -> bool {
     address
         == &Address::from_str(NATIVE_ERC20_ADDRESS).expect("Should not fail converintg to address")
@@ -961,6 +2686,15 @@ impl From<ChainId> for Nat {
     }
 }
 
+// Hash of a signed transaction, the keccak256 of its raw RLP encoding
+pub fn signed_transaction_hash(raw_transaction: &str) -> Option<TransactionHash> {
+    let raw_transaction = raw_transaction
+        .strip_prefix("0x")
+        .unwrap_or(raw_transaction);
+    let bytes = hex::decode(raw_transaction).ok()?;
+    Some(format!("0x{}", hex::encode(Keccak256::hash(bytes))))
+}
+
 pub fn nat_to_ledger_burn_index(value: &Nat) -> LedgerBurnIndex {
     LedgerBurnIndex::new(nat_to_u64(value))
 }
@@ -1000,7 +2734,7 @@ pub fn nat_to_u8(value: &Nat) -> u8 {
 pub fn checked_nat_to_u8(value: &Nat) -> Option<u8> {
     value.0.to_u8()
 }
-#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct ChainId(pub u64);
 
@@ -1047,7 +2781,32 @@ thread_local! {
                 supported_twin_appic_tokens:BTreeMap::init(supported_appic_tokens_memory_id()),
                 evm_token_list:BTreeMap::init(evm_token_list_id()),
                 icp_token_list:BTreeMap::init(icp_token_list_id()),
-                twin_erc20_requests: BTreeMap::init(erc20_twin_ledger_requests_id())
+                twin_erc20_requests: BTreeMap::init(erc20_twin_ledger_requests_id()),
+                address_tx_index: BTreeMap::init(address_tx_index_memory()),
+                principal_tx_index: BTreeMap::init(principal_tx_index_memory()),
+                icp_to_evm_hash_index: BTreeMap::init(icp_to_evm_hash_index_memory()),
+                evm_to_icp_mint_index: BTreeMap::init(evm_to_icp_mint_index_memory()),
+                scrape_health: BTreeMap::init(scrape_health_memory()),
+                disabled_minters: BTreeMap::init(disabled_minters_memory()),
+                failed_scrape_ranges: BTreeMap::init(failed_scrape_ranges_memory()),
+                scrape_intervals: BTreeMap::init(scrape_intervals_memory()),
+                transaction_timelines: BTreeMap::init(transaction_timelines_memory()),
+                transaction_attempts: BTreeMap::init(transaction_attempts_memory()),
+                reimbursements: BTreeMap::init(reimbursements_memory()),
+                synced_blocks: BTreeMap::init(synced_blocks_memory()),
+                skipped_blocks: BTreeMap::init(skipped_blocks_memory()),
+                deposit_latencies: BTreeMap::init(deposit_latencies_memory()),
+                bridge_stats: BTreeMap::init(bridge_stats_memory()),
+                open_transfers: BTreeMap::init(open_transfers_memory()),
+                transfer_usd_values: BTreeMap::init(transfer_usd_values_memory()),
+                usd_volumes: BTreeMap::init(usd_volumes_memory()),
+                hourly_token_prices: BTreeMap::init(hourly_token_prices_memory()),
+                daily_token_prices: BTreeMap::init(daily_token_prices_memory()),
+                evm_token_prices: BTreeMap::init(evm_token_prices_memory()),
+                evm_token_edits: BTreeMap::init(evm_token_edits_memory()),
+                pending_index_backfill: BTreeMap::init(pending_index_backfill_memory()),
+                transaction_time_index: BTreeMap::init(transaction_time_index_memory()),
+                transaction_status_counts: BTreeMap::init(transaction_status_counts_memory()),
 
             })
     );
@@ -1056,8 +2815,20 @@ thread_local! {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bridge_stats::{NANOS_PER_DAY, NANOS_PER_SECOND};
+    use crate::endpoints::{CandidTransferStats, CandidUsdVolume};
+    use crate::numeric::UsdValue;
+    use crate::price_history::NANOS_PER_HOUR;
     use std::time::Instant;
 
+    const SECOND: u64 = NANOS_PER_SECOND;
+    const HOUR: u64 = NANOS_PER_HOUR;
+    const DAY: u64 = NANOS_PER_DAY;
+
+    fn usd_price(price: &str) -> UsdPrice {
+        UsdPrice::from_decimal_str(price, USD_DECIMALS).unwrap()
+    }
+
     #[test]
     fn compare_bincode_and_ciborium() {
         let tx_identifier: EvmToIcpTxIdentifier = EvmToIcpTxIdentifier(
@@ -1104,4 +2875,1126 @@ mod tests {
             ciborium_serialization_time, ciborium_deserialization_time, ciborium_size
         );
     }
+
+    fn evm_to_icp_tx(
+        transaction_hash: &str,
+        from_address: Address,
+        principal: Principal,
+        time: u64,
+    ) -> EvmToIcpTx {
+        EvmToIcpTx {
+            from_address,
+            transaction_hash: transaction_hash.to_string(),
+            value: Erc20TokenAmount::from(1_000_u64),
+            ledger_mint_index: None,
+            block_number: None,
+            actual_received: None,
+            principal,
+            subaccount: None,
+            chain_id: ChainId(1),
+            total_gas_spent: None,
+            erc20_contract_address: Address::from_str(NATIVE_ERC20_ADDRESS).unwrap(),
+            icrc_ledger_id: None,
+            status: EvmToIcpStatus::Accepted,
+            verified: true,
+            time,
+            operator: Operator::DfinityCkEthMinter,
+        }
+    }
+
+    fn icp_to_evm_tx(destination: Address, from: Principal, time: u64) -> IcpToEvmTx {
+        IcpToEvmTx {
+            transaction_hash: None,
+            native_ledger_burn_index: LedgerBurnIndex::new(7),
+            withdrawal_amount: Erc20TokenAmount::from(1_000_u64),
+            actual_received: None,
+            destination,
+            from,
+            chain_id: ChainId(1),
+            from_subaccount: None,
+            time,
+            max_transaction_fee: None,
+            effective_gas_price: None,
+            gas_used: None,
+            total_gas_spent: None,
+            erc20_ledger_burn_index: None,
+            erc20_contract_address: Address::from_str(NATIVE_ERC20_ADDRESS).unwrap(),
+            icrc_ledger_id: None,
+            verified: true,
+            status: IcpToEvmStatus::Accepted,
+            operator: Operator::DfinityCkEthMinter,
+        }
+    }
+
+    fn evm_token(chain_id: ChainId, address: &str, symbol: &str) -> EvmToken {
+        EvmToken {
+            chain_id,
+            erc20_contract_address: Address::from_str(address).unwrap(),
+            name: symbol.to_string(),
+            decimals: 18,
+            symbol: symbol.to_string(),
+            logo: "".to_string(),
+        }
+    }
+
+    fn icp_token(ledger_id: Principal, symbol: &str) -> IcpToken {
+        IcpToken {
+            ledger_id,
+            name: symbol.to_string(),
+            decimals: 18,
+            symbol: symbol.to_string(),
+            usd_price: UsdPrice::ZERO,
+            logo: "".to_string(),
+            fee: Erc20TokenAmount::new(10_000),
+            token_type: IcpTokenType::ICRC2,
+            rank: None,
+        }
+    }
+
+    fn transaction_times(page: &TransactionsPage) -> Vec<u64> {
+        page.transactions
+            .iter()
+            .map(|tx| match tx {
+                Transaction::EvmToIcp(tx) => tx.time,
+                Transaction::IcpToEvm(tx) => tx.time,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_encode_and_decode_transaction_cursor() {
+        let cursor = TransactionCursor::new(
+            1_700_000_000,
+            TransactionKey::IcpToEvm(IcpToEvmIdentifier::new(
+                LedgerBurnIndex::new(7),
+                ChainId(56),
+            )),
+        );
+
+        assert_eq!(
+            TransactionCursor::from_str(&cursor.to_string()).unwrap(),
+            cursor
+        );
+        assert!(TransactionCursor::from_str("not a cursor").is_err());
+    }
+
+    #[test]
+    fn should_page_through_transactions_for_address() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+
+        mutate_state(|s| {
+            for time in 0..5_u64 {
+                let transaction_hash = format!("0x{:064x}", time);
+                s.record_new_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1)),
+                    evm_to_icp_tx(&transaction_hash, address, principal, time),
+                );
+            }
+        });
+
+        let first_page = read_state(|s| s.get_transactions_page_for_address(address, None, 2));
+        assert_eq!(first_page.total_count, Some(5));
+        assert_eq!(transaction_times(&first_page), vec![4, 3]);
+
+        let cursor = TransactionCursor::from_str(&first_page.next_cursor.unwrap()).unwrap();
+        let second_page =
+            read_state(|s| s.get_transactions_page_for_principal(principal, Some(cursor), 2));
+        assert_eq!(transaction_times(&second_page), vec![2, 1]);
+        assert_eq!(second_page.total_count, None);
+
+        let cursor = TransactionCursor::from_str(&second_page.next_cursor.unwrap()).unwrap();
+        let last_page =
+            read_state(|s| s.get_transactions_page_for_address(address, Some(cursor), 2));
+        assert_eq!(transaction_times(&last_page), vec![0]);
+        assert_eq!(last_page.next_cursor, None);
+    }
+
+    #[test]
+    fn should_backfill_indexes_of_existing_transactions_in_chunks() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+
+        // Recorded without going through record_new_*, like transactions stored before the
+        // indexes existed
+        mutate_state(|s| {
+            for time in 0..3_u64 {
+                let transaction_hash = format!("0x{:064x}", time);
+                s.evm_to_icp_txs.insert(
+                    EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1)),
+                    evm_to_icp_tx(&transaction_hash, address, principal, time),
+                );
+            }
+            s.icp_to_evm_txs.insert(
+                IcpToEvmIdentifier::new(LedgerBurnIndex::new(7), ChainId(1)),
+                IcpToEvmTx {
+                    transaction_hash: Some("0xabc".to_string()),
+                    ..icp_to_evm_tx(address, principal, 3)
+                },
+            );
+            s.start_index_backfill();
+        });
+
+        assert!(mutate_state(|s| s.index_existing_transactions(2)));
+        // Another upgrade does not restart a backfill in progress
+        mutate_state(|s| s.start_index_backfill());
+        assert!(!mutate_state(|s| s.index_existing_transactions(2)));
+        assert!(!read_state(|s| s.is_index_backfill_pending()));
+
+        let page = read_state(|s| s.get_transactions_page_for_principal(principal, None, 10));
+        assert_eq!(transaction_times(&page), vec![3, 2, 1, 0]);
+        assert_eq!(
+            read_state(|s| s
+                .transaction_status_counts
+                .get(&TimelineStatus::EvmToIcp(EvmToIcpStatus::Accepted))),
+            Some(3)
+        );
+        assert_eq!(
+            read_state(|s| s.get_transaction_key_by_search_params(
+                TransactionSearchParam::TxHash("0xabc".to_string()),
+                ChainId(1)
+            )),
+            Some(TransactionKey::IcpToEvm(IcpToEvmIdentifier::new(
+                LedgerBurnIndex::new(7),
+                ChainId(1)
+            )))
+        );
+
+        // Populated indexes are not backfilled again
+        mutate_state(|s| s.start_index_backfill());
+        assert!(!read_state(|s| s.is_index_backfill_pending()));
+    }
+
+    #[test]
+    fn should_count_transactions_per_status() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+        let counts = || {
+            read_state(|s| {
+                s.transaction_status_counts
+                    .iter()
+                    .collect::<Vec<(TimelineStatus, u64)>>()
+            })
+        };
+
+        mutate_state(|s| {
+            for time in 0..3_u64 {
+                let transaction_hash = format!("0x{:064x}", time);
+                s.record_new_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1)),
+                    evm_to_icp_tx(&transaction_hash, address, principal, time),
+                );
+            }
+            let transaction_hash = format!("0x{:064x}", 0);
+            s.record_invalid_evm_to_icp(
+                EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1)),
+                "reverted".to_string(),
+            );
+            s.record_new_icp_to_evm(
+                IcpToEvmIdentifier::new(LedgerBurnIndex::new(7), ChainId(1)),
+                icp_to_evm_tx(address, principal, 3),
+            );
+            s.remove_unverified_icp_to_evm(&IcpToEvmIdentifier::new(
+                LedgerBurnIndex::new(7),
+                ChainId(1),
+            ));
+        });
+
+        assert_eq!(
+            counts(),
+            vec![
+                (TimelineStatus::EvmToIcp(EvmToIcpStatus::Accepted), 2),
+                (
+                    TimelineStatus::EvmToIcp(EvmToIcpStatus::Invalid(String::new())),
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_return_most_recent_transactions_first() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+
+        mutate_state(|s| {
+            for time in [3_u64, 0, 4, 1, 2] {
+                let transaction_hash = format!("0x{:064x}", time);
+                s.record_new_evm_to_icp(
+                    EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1)),
+                    evm_to_icp_tx(&transaction_hash, address, principal, time),
+                );
+            }
+        });
+
+        let recent = read_state(|s| s.get_recent_transactions(3));
+        let times: Vec<u64> = recent
+            .iter()
+            .map(|tx| match tx {
+                Transaction::EvmToIcp(tx) => tx.time,
+                Transaction::IcpToEvm(tx) => tx.time,
+            })
+            .collect();
+        assert_eq!(times, vec![4, 3, 2]);
+
+        // Removed transactions leave the time index
+        mutate_state(|s| {
+            s.remove_unverified_evm_to_icp(&EvmToIcpTxIdentifier::new(
+                &format!("0x{:064x}", 4),
+                ChainId(1),
+            ))
+        });
+        assert_eq!(read_state(|s| s.get_recent_transactions(5)).len(), 4);
+    }
+
+    #[test]
+    fn should_track_consecutive_scrape_failures() {
+        let minter_key = MinterKey(ChainId(56), Operator::AppicMinter);
+
+        mutate_state(|s| {
+            s.record_scrape_failure(&minter_key, "first".to_string(), 1);
+            s.record_scrape_failure(&minter_key, "second".to_string(), 2);
+        });
+
+        let health = read_state(|s| s.get_scrape_health(&minter_key));
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.last_error, Some("second".to_string()));
+        assert_eq!(health.last_success_time, None);
+
+        mutate_state(|s| s.record_scrape_success(&minter_key, 3));
+
+        let health = read_state(|s| s.get_scrape_health(&minter_key));
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_success_time, Some(3));
+        assert_eq!(health.last_error_time, Some(2));
+    }
+
+    #[test]
+    fn should_not_scrape_disabled_or_removed_minters() {
+        let minter = |chain_id: u64| Minter {
+            id: Principal::from_text("2vxsx-fae").unwrap(),
+            last_observed_event: 0,
+            last_scraped_event: 0,
+            operator: Operator::AppicMinter,
+            evm_to_icp_fee: Erc20TokenAmount::ZERO,
+            icp_to_evm_fee: Erc20TokenAmount::ZERO,
+            chain_id: ChainId(chain_id),
+        };
+        let active_chains = || {
+            read_state(|s| {
+                s.get_active_minters()
+                    .into_iter()
+                    .map(|(minter_key, _minter)| minter_key.chain_id().0)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        mutate_state(|s| {
+            s.record_minter(minter(1));
+            s.record_minter(minter(56));
+            s.record_minter(minter(137));
+        });
+
+        let bsc = MinterKey(ChainId(56), Operator::AppicMinter);
+        mutate_state(|s| s.disable_minter(&bsc));
+        assert_eq!(active_chains(), vec![1, 137]);
+        assert!(read_state(|s| s.if_minter_exists(&bsc)));
+
+        mutate_state(|s| s.enable_minter(&bsc));
+        assert_eq!(active_chains(), vec![1, 56, 137]);
+
+        assert!(mutate_state(|s| s.remove_minter(&bsc)).is_some());
+        assert_eq!(active_chains(), vec![1, 137]);
+        assert!(mutate_state(|s| s.remove_minter(&bsc)).is_none());
+    }
+
+    #[test]
+    fn should_not_move_status_back_when_replaying_events() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+        let transaction_hash = format!("0x{:064x}", 1);
+        let identifier = EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1));
+
+        let accept = |s: &mut State| {
+            s.record_accepted_evm_to_icp(
+                identifier.clone(),
+                transaction_hash.clone(),
+                Nat::from(100_u64),
+                address.to_string(),
+                Nat::from(1_000_u64),
+                principal,
+                NATIVE_ERC20_ADDRESS.to_string(),
+                None,
+                ChainId(1),
+                Operator::DfinityCkEthMinter,
+                10,
+            )
+        };
+
+        mutate_state(|s| {
+            accept(s);
+            s.record_minted_evm_to_icp(
+                identifier.clone(),
+                Erc20TokenAmount::ZERO,
+                LedgerMintIndex::new(5),
+                20,
+            );
+            // Replaying the accepted deposit event
+            accept(s);
+        });
+
+        let tx = read_state(|s| s.evm_to_icp_txs.get(&identifier)).unwrap();
+        assert_eq!(tx.status, EvmToIcpStatus::Minted);
+        assert_eq!(tx.ledger_mint_index, Some(LedgerMintIndex::new(5)));
+
+        assert_eq!(
+            IcpToEvmStatus::Successful.advance_to(IcpToEvmStatus::SignedTransaction),
+            IcpToEvmStatus::Successful
+        );
+        assert_eq!(
+            IcpToEvmStatus::ReplacedTransaction.advance_to(IcpToEvmStatus::SignedTransaction),
+            IcpToEvmStatus::SignedTransaction
+        );
+    }
+
+    #[test]
+    fn should_back_off_and_clear_failed_scrape_ranges() {
+        let minter_key = MinterKey(ChainId(56), Operator::AppicMinter);
+        let other_minter_key = MinterKey(ChainId(1), Operator::AppicMinter);
+        let retry_delay = |attempts: u32| 10 * 2_u64.pow(attempts - 1);
+
+        mutate_state(|s| {
+            s.record_failed_scrape_range(
+                &minter_key,
+                100,
+                199,
+                "first".to_string(),
+                0,
+                retry_delay,
+            );
+            s.record_failed_scrape_range(
+                &minter_key,
+                100,
+                199,
+                "second".to_string(),
+                10,
+                retry_delay,
+            );
+            s.record_failed_scrape_range(
+                &minter_key,
+                300,
+                399,
+                "error".to_string(),
+                0,
+                retry_delay,
+            );
+            s.record_failed_scrape_range(
+                &other_minter_key,
+                100,
+                199,
+                "error".to_string(),
+                0,
+                retry_delay,
+            );
+        });
+
+        let range = read_state(|s| {
+            s.failed_scrape_ranges
+                .get(&FailedScrapeRangeKey(minter_key.clone(), 100))
+        })
+        .unwrap();
+        assert_eq!(range.attempts, 2);
+        assert_eq!(range.last_error, "second".to_string());
+        assert_eq!(range.next_retry_time, 30);
+
+        assert_eq!(read_state(|s| s.get_due_failed_scrape_ranges(10)).len(), 2);
+        assert_eq!(read_state(|s| s.get_due_failed_scrape_ranges(30)).len(), 3);
+
+        mutate_state(|s| s.remove_failed_scrape_ranges_within(&minter_key, 50, 250));
+        let remaining: Vec<FailedScrapeRangeKey> = read_state(|s| {
+            s.get_failed_scrape_ranges()
+                .into_iter()
+                .map(|(key, _range)| key)
+                .collect()
+        });
+        assert_eq!(
+            remaining,
+            vec![
+                FailedScrapeRangeKey(other_minter_key, 100),
+                FailedScrapeRangeKey(minter_key, 300)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_keep_indexes_in_sync_with_transactions() {
+        let user_address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let minter_address =
+            Address::from_str("0xb44b5e756a894775fc32eddf3314bb1b1944dc34").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+        let transaction_hash = format!("0x{:064x}", 1);
+        let identifier = EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1));
+
+        mutate_state(|s| {
+            s.record_new_evm_to_icp(
+                identifier.clone(),
+                evm_to_icp_tx(&transaction_hash, user_address, principal, 10),
+            );
+            // The minter reports a different sender than the one submitted by the user
+            s.record_new_evm_to_icp(
+                identifier.clone(),
+                evm_to_icp_tx(&transaction_hash, minter_address, principal, 10),
+            );
+        });
+
+        assert!(read_state(|s| s.get_transaction_for_address(user_address)).is_empty());
+        assert_eq!(
+            read_state(|s| s.get_transaction_for_address(minter_address)).len(),
+            1
+        );
+        assert_eq!(
+            read_state(|s| s.get_transaction_for_principal(principal)).len(),
+            1
+        );
+
+        mutate_state(|s| s.remove_unverified_evm_to_icp(&identifier));
+
+        assert!(read_state(|s| s.get_transaction_for_address(minter_address)).is_empty());
+        assert!(read_state(|s| s.get_transaction_for_principal(principal)).is_empty());
+        assert!(read_state(
+            |s| s.address_tx_index.is_empty() && s.principal_tx_index.is_empty()
+        ));
+    }
+
+    #[test]
+    fn should_find_minted_transaction_by_mint_index() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+        let transaction_hash = format!("0x{:064x}", 2);
+        let identifier = EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1));
+
+        mutate_state(|s| {
+            s.record_new_evm_to_icp(
+                identifier.clone(),
+                evm_to_icp_tx(&transaction_hash, address, principal, 10),
+            );
+            s.record_minted_evm_to_icp(
+                identifier,
+                Erc20TokenAmount::ZERO,
+                LedgerMintIndex::new(42),
+                20,
+            );
+        });
+
+        let search_result = read_state(|s| {
+            s.get_transaction_by_search_params(
+                TransactionSearchParam::TxMintId(Nat::from(42_u64)),
+                ChainId(1),
+            )
+        });
+        match search_result {
+            Some(Transaction::EvmToIcp(tx)) => assert_eq!(tx.transaction_hash, transaction_hash),
+            _ => panic!("expected minted evm_to_icp transaction"),
+        }
+
+        let other_chain_result = read_state(|s| {
+            s.get_transaction_by_search_params(
+                TransactionSearchParam::TxMintId(Nat::from(42_u64)),
+                ChainId(56),
+            )
+        });
+        assert_eq!(other_chain_result, None);
+    }
+
+    #[test]
+    fn should_record_reimbursement_of_failed_withdrawal() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+        let ledger_id = Principal::from_slice(&[1, 2, 3]);
+        let identifier = IcpToEvmIdentifier::new(LedgerBurnIndex::new(7), ChainId(1));
+
+        mutate_state(|s| {
+            s.record_new_icp_to_evm(identifier.clone(), icp_to_evm_tx(address, principal, 10));
+            s.record_reimbursed_icp_to_evm(
+                identifier.clone(),
+                Reimbursement {
+                    reimbursed_amount: Erc20TokenAmount::from(990_u64),
+                    ledger_id: Some(ledger_id),
+                    reimbursed_in_block: Some(LedgerMintIndex::new(21)),
+                    transaction_hash: Some(format!("0x{:064x}", 3)),
+                },
+            );
+        });
+
+        let tx = read_state(|s| s.icp_to_evm_txs.get(&identifier)).unwrap();
+        assert_eq!(tx.status, IcpToEvmStatus::Reimbursed);
+        let reimbursement = read_state(|s| s.reimbursements.get(&identifier));
+        assert_eq!(
+            CandidIcpToEvm::from((tx, reimbursement)).reimbursement,
+            Some(crate::endpoints::CandidReimbursement {
+                reimbursed_amount: Nat::from(990_u64),
+                ledger_id: Some(ledger_id),
+                reimbursed_in_block: Some(Nat::from(21_u64)),
+                transaction_hash: Some(format!("0x{:064x}", 3)),
+            })
+        );
+    }
+
+    #[test]
+    fn should_estimate_mint_time_from_latest_deposit_latencies() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+
+        let accept = |s: &mut State, transaction_hash: &str, accepted_at: u64| {
+            let identifier = EvmToIcpTxIdentifier::new(&transaction_hash.to_string(), ChainId(1));
+            s.record_accepted_evm_to_icp(
+                identifier.clone(),
+                transaction_hash.to_string(),
+                Nat::from(100_u64),
+                address.to_string(),
+                Nat::from(1_000_u64),
+                principal,
+                NATIVE_ERC20_ADDRESS.to_string(),
+                None,
+                ChainId(1),
+                Operator::DfinityCkEthMinter,
+                accepted_at,
+            );
+            s.record_timeline_entry(
+                TransactionKey::EvmToIcp(identifier.clone()),
+                accepted_at,
+                TimelineStatus::EvmToIcp(EvmToIcpStatus::Accepted),
+                accepted_at,
+            );
+            identifier
+        };
+
+        let (minted, pending) = mutate_state(|s| {
+            let minted = accept(s, &format!("0x{:064x}", 1), 10 * SECOND);
+            let mint = |s: &mut State| {
+                s.record_minted_evm_to_icp(
+                    minted.clone(),
+                    Erc20TokenAmount::ZERO,
+                    LedgerMintIndex::new(1),
+                    70 * SECOND,
+                )
+            };
+            mint(s);
+            // Replaying the mint event does not add a sample
+            mint(s);
+
+            let pending = accept(s, &format!("0x{:064x}", 2), 100 * SECOND);
+            (minted, pending)
+        });
+
+        let minter_key = MinterKey(ChainId(1), Operator::DfinityCkEthMinter);
+        assert_eq!(
+            read_state(|s| s.get_deposit_latencies(&minter_key)).samples,
+            vec![60 * SECOND]
+        );
+        assert_eq!(
+            read_state(|s| s.estimate_deposit_mint_time(&pending)),
+            Some(CandidDepositEta {
+                status: EvmToIcpStatus::Accepted,
+                block_number: Some(Nat::from(100_u64)),
+                minter_synced_block: None,
+                latency_samples: 1,
+                median_latency_seconds: Some(60),
+                estimated_mint_time: Some(160 * SECOND),
+            })
+        );
+        assert_eq!(read_state(|s| s.estimate_deposit_mint_time(&minted)), None);
+    }
+
+    #[test]
+    fn should_aggregate_bridge_stats_of_accepted_deposits() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+
+        let accept = |s: &mut State, index: u64| {
+            let transaction_hash = format!("0x{:064x}", index);
+            let identifier = EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1));
+            s.record_accepted_evm_to_icp(
+                identifier.clone(),
+                transaction_hash,
+                Nat::from(100_u64),
+                address.to_string(),
+                Nat::from(1_000_u64),
+                principal,
+                NATIVE_ERC20_ADDRESS.to_string(),
+                None,
+                ChainId(1),
+                Operator::DfinityCkEthMinter,
+                10 * SECOND,
+            );
+            identifier
+        };
+
+        mutate_state(|s| {
+            for index in 1..=3 {
+                let identifier = accept(s, index);
+                s.record_minted_evm_to_icp(
+                    identifier.clone(),
+                    Erc20TokenAmount::ZERO,
+                    LedgerMintIndex::new(index),
+                    (10 + 20 * index) * SECOND,
+                );
+                // Replayed events are not counted twice
+                accept(s, index);
+                s.record_minted_evm_to_icp(
+                    identifier,
+                    Erc20TokenAmount::ZERO,
+                    LedgerMintIndex::new(index),
+                    100 * SECOND,
+                );
+            }
+
+            let invalid = accept(s, 4);
+            s.record_invalid_evm_to_icp(invalid.clone(), "invalid principal".to_string());
+            s.record_quarantined_evm_to_icp(invalid);
+        });
+
+        let stats = read_state(|s| s.get_bridge_stats(StatsWindow::AllTime, 20 * SECOND));
+        assert_eq!(
+            stats,
+            vec![CandidBridgeStats {
+                chain_id: Nat::from(1_u64),
+                operator: Operator::DfinityCkEthMinter,
+                erc20_contract_address: NATIVE_ERC20_ADDRESS.to_string(),
+                deposits: CandidTransferStats {
+                    count: 4,
+                    volume: Nat::from(4_000_u64),
+                    completed: 3,
+                    failed: 1,
+                    failure_rate: 0.25,
+                    median_latency_seconds: Some(45),
+                    p95_latency_seconds: Some(60),
+                },
+                withdrawals: CandidTransferStats {
+                    count: 0,
+                    volume: Nat::from(0_u64),
+                    completed: 0,
+                    failed: 0,
+                    failure_rate: 0.0,
+                    median_latency_seconds: None,
+                    p95_latency_seconds: None,
+                },
+            }]
+        );
+
+        // Buckets of earlier days are left out of shorter windows
+        let two_days_later = 2 * 24 * 60 * 60 * SECOND;
+        assert_eq!(
+            read_state(|s| s.get_bridge_stats(StatsWindow::LastDay, two_days_later)),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn should_skip_outcomes_of_transfers_not_counted_as_accepted() {
+        let address = Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap();
+        let principal = Principal::from_text("2vxsx-fae").unwrap();
+
+        // Stored before bridge stats were recorded, so never counted as accepted
+        let transaction_hash = format!("0x{:064x}", 1);
+        let identifier = EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1));
+        mutate_state(|s| {
+            s.record_new_evm_to_icp(
+                identifier.clone(),
+                evm_to_icp_tx(&transaction_hash, address, principal, 10 * SECOND),
+            );
+            s.record_minted_evm_to_icp(
+                identifier,
+                Erc20TokenAmount::ZERO,
+                LedgerMintIndex::new(1),
+                30 * SECOND,
+            );
+        });
+
+        let stats = read_state(|s| s.get_bridge_stats(StatsWindow::AllTime, 30 * SECOND));
+        assert!(stats
+            .iter()
+            .all(|stats| stats.deposits.count == 0 && stats.deposits.completed == 0));
+        assert!(read_state(|s| s.open_transfers.is_empty()));
+    }
+
+    #[test]
+    fn should_snapshot_usd_value_of_accepted_deposits() {
+        let native = Address::from_str(NATIVE_ERC20_ADDRESS).unwrap();
+        let ledger_id = Principal::from_text("ss2fx-dyaaa-aaaar-qacoq-cai").unwrap();
+        let evm_token = EvmToken {
+            chain_id: ChainId(1),
+            erc20_contract_address: native,
+            name: "Ethereum".to_string(),
+            decimals: 18,
+            symbol: "ETH".to_string(),
+            logo: "".to_string(),
+        };
+        let icp_token = IcpToken {
+            ledger_id,
+            name: "ckETH".to_string(),
+            decimals: 18,
+            symbol: "ckETH".to_string(),
+            usd_price: usd_price("2000"),
+            logo: "".to_string(),
+            fee: Erc20TokenAmount::new(2_000_000_000_000),
+            token_type: IcpTokenType::ICRC2,
+            rank: Some(1),
+        };
+
+        let accept = |s: &mut State, index: u64, erc20_contract_address: &str, value: u128| {
+            let transaction_hash = format!("0x{:064x}", index);
+            let identifier = EvmToIcpTxIdentifier::new(&transaction_hash, ChainId(1));
+            s.record_accepted_evm_to_icp(
+                identifier.clone(),
+                transaction_hash,
+                Nat::from(100_u64),
+                "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string(),
+                Nat::from(value),
+                Principal::from_text("2vxsx-fae").unwrap(),
+                erc20_contract_address.to_string(),
+                None,
+                ChainId(1),
+                Operator::DfinityCkEthMinter,
+                DAY + index,
+            );
+            TransactionKey::EvmToIcp(identifier)
+        };
+
+        let (valued, unknown_token) = mutate_state(|s| {
+            s.record_evm_token(Erc20Identifier(native, ChainId(1)), evm_token.clone());
+            s.record_icp_token(ledger_id, icp_token.clone());
+            s.supported_ckerc20_tokens.insert(
+                Erc20Identifier(native, ChainId(1)),
+                BridgePair {
+                    icp_token,
+                    evm_token,
+                },
+            );
+
+            let valued = accept(s, 1, NATIVE_ERC20_ADDRESS, 1_500_000_000_000_000_000);
+            // Later prices do not change the value of transfers that were already accepted
+            s.update_icp_token_usd_price(ledger_id, usd_price("3000"), DAY);
+            accept(s, 2, NATIVE_ERC20_ADDRESS, 500_000_000_000_000_000);
+            let unknown_token = accept(
+                s,
+                3,
+                "0xdac17f958d2ee523a2206206994597c13d831ec7",
+                1_000_000,
+            );
+            (valued, unknown_token)
+        });
+
+        assert_eq!(
+            read_state(|s| s.get_transfer_usd_value(&valued)),
+            Some(TransferUsdValue {
+                usd_price: usd_price("2000"),
+                usd_value: UsdValue::new(3_000_000_000_000_000_000_000),
+            })
+        );
+        assert_eq!(
+            read_state(|s| s.get_transfer_usd_value(&unknown_token)),
+            None
+        );
+        assert_eq!(
+            read_state(|s| s.get_usd_volumes(DAY, 2 * DAY - 1)),
+            vec![CandidDailyUsdVolume {
+                day_start: DAY,
+                chain_id: Nat::from(1_u64),
+                operator: Operator::DfinityCkEthMinter,
+                erc20_contract_address: NATIVE_ERC20_ADDRESS.to_string(),
+                deposits: CandidUsdVolume {
+                    transfers: 2,
+                    usd_value: "4500".to_string(),
+                    usd_value_e18: Nat::from(4_500_000_000_000_000_000_000_u128),
+                },
+                withdrawals: CandidUsdVolume {
+                    transfers: 0,
+                    usd_value: "0".to_string(),
+                    usd_value_e18: Nat::from(0_u8),
+                },
+            }]
+        );
+        assert_eq!(read_state(|s| s.get_usd_volumes(0, DAY - 1)), vec![]);
+    }
+
+    #[test]
+    fn should_downsample_token_prices_older_than_thirty_days_to_daily() {
+        let ledger_id = Principal::from_text("ss2fx-dyaaa-aaaar-qacoq-cai").unwrap();
+        let point = |timestamp: u64, price: &str| CandidPricePoint {
+            timestamp,
+            usd_price: price.to_string(),
+            usd_price_e18: usd_price(price).into(),
+        };
+
+        mutate_state(|s| {
+            s.record_icp_token(
+                ledger_id,
+                IcpToken {
+                    ledger_id,
+                    name: "ckETH".to_string(),
+                    decimals: 18,
+                    symbol: "ckETH".to_string(),
+                    usd_price: UsdPrice::ZERO,
+                    logo: "".to_string(),
+                    fee: Erc20TokenAmount::new(2_000_000_000_000),
+                    token_type: IcpTokenType::ICRC2,
+                    rank: Some(1),
+                },
+            );
+            s.update_icp_token_usd_price(ledger_id, usd_price("2000"), 10 * HOUR);
+            s.update_icp_token_usd_price(ledger_id, usd_price("2100"), 11 * HOUR);
+            // A later price of the same hour replaces the earlier one
+            s.update_icp_token_usd_price(ledger_id, usd_price("2200"), 11 * HOUR + 30);
+            // Prices of tokens that are not listed are ignored
+            s.update_icp_token_usd_price(Principal::anonymous(), usd_price("1"), 11 * HOUR);
+        });
+
+        assert_eq!(
+            read_state(|s| s.get_token_price_history(ledger_id, 0, DAY)),
+            vec![point(10 * HOUR, "2000"), point(11 * HOUR, "2200")]
+        );
+        assert_eq!(
+            read_state(|s| s.get_token_price_history(ledger_id, 11 * HOUR, 11 * HOUR)),
+            vec![point(11 * HOUR, "2200")]
+        );
+
+        mutate_state(|s| {
+            s.update_icp_token_usd_price(ledger_id, usd_price("2300"), DAY + 12 * HOUR);
+            s.update_icp_token_usd_price(ledger_id, usd_price("2400"), 31 * DAY + 12 * HOUR);
+        });
+
+        assert_eq!(
+            read_state(|s| s.get_token_price_history(ledger_id, 0, u64::MAX)),
+            vec![
+                point(0, "2200"),
+                point(DAY + 12 * HOUR, "2300"),
+                point(31 * DAY + 12 * HOUR, "2400"),
+            ]
+        );
+        assert_eq!(
+            read_state(|s| s.get_token_price_history(Principal::anonymous(), 0, u64::MAX)),
+            vec![]
+        );
+
+        mutate_state(|s| s.remove_icp_token(&ledger_id));
+        assert_eq!(
+            read_state(|s| s.get_token_price_history(ledger_id, 0, u64::MAX)),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn should_keep_the_stored_layout_of_icp_token_prices() {
+        let ledger_id = Principal::from_text("ss2fx-dyaaa-aaaar-qacoq-cai").unwrap();
+        let stored_token = bincode::serialize(&(
+            ledger_id,
+            "ckETH".to_string(),
+            18_u8,
+            "ckETH".to_string(),
+            "2000.5".to_string(),
+            "".to_string(),
+            Erc20TokenAmount::new(2_000_000_000_000),
+            IcpTokenType::ICRC2,
+            Some(1_u32),
+        ))
+        .unwrap();
+
+        let token = IcpToken::from_bytes(Cow::Owned(stored_token.clone()));
+        assert_eq!(token.usd_price, usd_price("2000.5"));
+        assert_eq!(token.to_bytes().into_owned(), stored_token);
+    }
+
+    #[test]
+    fn should_price_evm_tokens_from_their_icp_twin_or_pushed_price() {
+        let bridged = evm_token(
+            ChainId(56),
+            "0x55d398326f99059ff775485246999027b3197955",
+            "USDT",
+        );
+        let unbridged = evm_token(
+            ChainId(56),
+            "0x0e09fabb73bd3ade0a17ecc321fd13a19e81ce82",
+            "CAKE",
+        );
+        let ledger_id = Principal::from_text("cngnf-vqaaa-aaaar-qag4q-cai").unwrap();
+        let icp_token = icp_token(ledger_id, "icUSDT");
+
+        mutate_state(|s| {
+            s.record_evm_tokens_bulk(vec![bridged.clone(), unbridged.clone()]);
+            s.record_icp_token(ledger_id, icp_token.clone());
+            s.supported_twin_appic_tokens.insert(
+                Erc20Identifier::from(&bridged),
+                BridgePair {
+                    icp_token,
+                    evm_token: bridged.clone(),
+                },
+            );
+
+            // A twin without a price falls back to the pushed price
+            s.set_evm_token_price(Erc20Identifier::from(&bridged), Some(usd_price("0.99")), 1);
+            s.set_evm_token_price(Erc20Identifier::from(&unbridged), Some(usd_price("2.5")), 1);
+        });
+        assert_eq!(
+            read_state(|s| s.get_candid_evm_token(bridged.clone())).usd_price,
+            "0.99"
+        );
+
+        mutate_state(|s| s.update_icp_token_usd_price(ledger_id, usd_price("1.001"), 2));
+        let (bridged_token, unbridged_token) = read_state(|s| {
+            (
+                s.get_candid_evm_token(bridged.clone()),
+                s.get_candid_evm_token(unbridged.clone()),
+            )
+        });
+        assert_eq!(bridged_token.usd_price, "1.001");
+        assert_eq!(
+            bridged_token.usd_price_e18,
+            Some(Nat::from(1_001_000_000_000_000_000_u64))
+        );
+        assert_eq!(unbridged_token.usd_price, "2.5");
+
+        mutate_state(|s| s.set_evm_token_price(Erc20Identifier::from(&unbridged), None, 3));
+        let unbridged_token = read_state(|s| s.get_candid_evm_token(unbridged));
+        assert_eq!(unbridged_token.usd_price, "0");
+        assert_eq!(unbridged_token.usd_price_e18, None);
+    }
+
+    #[test]
+    fn should_leave_hidden_evm_tokens_out_of_bridge_pairs() {
+        let shown = evm_token(
+            ChainId(56),
+            "0x55d398326f99059ff775485246999027b3197955",
+            "USDT",
+        );
+        let hidden = evm_token(
+            ChainId(56),
+            "0x0e09fabb73bd3ade0a17ecc321fd13a19e81ce82",
+            "CAKE",
+        );
+
+        mutate_state(|s| {
+            s.record_evm_tokens_bulk(vec![shown.clone(), hidden.clone()]);
+            s.supported_twin_appic_tokens.insert(
+                Erc20Identifier::from(&shown),
+                BridgePair {
+                    icp_token: icp_token(Principal::from_slice(&[1]), "icUSDT"),
+                    evm_token: shown.clone(),
+                },
+            );
+            s.supported_ckerc20_tokens.insert(
+                Erc20Identifier::from(&hidden),
+                BridgePair {
+                    icp_token: icp_token(Principal::from_slice(&[2]), "ckCAKE"),
+                    evm_token: hidden.clone(),
+                },
+            );
+        });
+        let bridged_symbols = || {
+            read_state(|s| s.get_suported_bridge_pairs())
+                .into_iter()
+                .map(|pair| pair.evm_token.symbol)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(bridged_symbols(), vec!["CAKE", "USDT"]);
+
+        mutate_state(|s| assert!(s.set_evm_token_hidden(&Erc20Identifier::from(&hidden), true)));
+        assert_eq!(bridged_symbols(), vec!["USDT"]);
+
+        mutate_state(|s| assert!(s.set_evm_token_hidden(&Erc20Identifier::from(&hidden), false)));
+        assert_eq!(bridged_symbols(), vec!["CAKE", "USDT"]);
+
+        // A deleted token is left out as well and no pair is published for it again
+        let ic_cake = icp_token(Principal::from_slice(&[3]), "icCAKE");
+        mutate_state(|s| {
+            assert!(s
+                .delete_evm_token(&Erc20Identifier::from(&hidden))
+                .is_some());
+            s.record_icp_token(ic_cake.ledger_id, ic_cake.clone());
+            assert_eq!(
+                s.record_added_twin_token(
+                    Operator::AppicMinter,
+                    Erc20Identifier::from(&hidden),
+                    "icCAKE",
+                    ic_cake.ledger_id,
+                    Some(ic_cake.fee),
+                ),
+                None
+            );
+        });
+        assert_eq!(bridged_symbols(), vec!["USDT"]);
+    }
+
+    #[test]
+    fn should_keep_runtime_edits_of_evm_tokens_when_merging_bundled_tokens() {
+        let untouched = evm_token(
+            ChainId(1),
+            "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            "USDT",
+        );
+        let updated = evm_token(
+            ChainId(1),
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+            "USDC",
+        );
+        let hidden = evm_token(
+            ChainId(1),
+            "0x6b175474e89094c44da98b954eedeac495271d0f",
+            "DAI",
+        );
+        let deleted = evm_token(
+            ChainId(1),
+            "0x2260fac5e5542a773aa44fbcfedf7c193bc2c599",
+            "WBTC",
+        );
+        let bundled = vec![
+            untouched.clone(),
+            updated.clone(),
+            hidden.clone(),
+            deleted.clone(),
+        ];
+
+        mutate_state(|s| {
+            assert_eq!(s.merge_bundled_evm_tokens(bundled.clone()), 4);
+            s.upsert_evm_token(EvmToken {
+                logo: "edited".to_string(),
+                ..updated.clone()
+            });
+            assert!(s.set_evm_token_hidden(&Erc20Identifier::from(&hidden), true));
+            assert!(s
+                .delete_evm_token(&Erc20Identifier::from(&deleted))
+                .is_some());
+        });
+
+        // Upgrades merge the bundled lists again
+        let merged = mutate_state(|s| s.merge_bundled_evm_tokens(bundled));
+
+        assert_eq!(merged, 1);
+        read_state(|s| {
+            assert_eq!(
+                s.get_evm_token_by_identifier(&Erc20Identifier::from(&untouched)),
+                Some(untouched)
+            );
+            assert_eq!(
+                s.get_evm_token_by_identifier(&Erc20Identifier::from(&updated))
+                    .map(|token| token.logo),
+                Some("edited".to_string())
+            );
+            assert!(s.is_evm_token_hidden(&Erc20Identifier::from(&hidden)));
+            assert!(s
+                .get_evm_token_by_identifier(&Erc20Identifier::from(&hidden))
+                .is_some());
+            assert_eq!(
+                s.get_evm_token_by_identifier(&Erc20Identifier::from(&deleted)),
+                None
+            );
+        });
+
+        // Adding a deleted token back makes it a runtime managed token again
+        mutate_state(|s| s.upsert_evm_token(deleted.clone()));
+        assert!(
+            read_state(|s| s.get_evm_token_by_identifier(&Erc20Identifier::from(&deleted)))
+                .is_some()
+        );
+    }
 }
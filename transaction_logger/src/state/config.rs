@@ -1,4 +1,6 @@
 use super::*;
+use ic_stable_structures::storable::Bound;
+use ic_stable_structures::Memory;
 
 thread_local! {
     static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> = RefCell::new(
@@ -55,6 +57,204 @@ pub fn erc20_twin_ledger_requests_id() -> StableMemory {
     MEMORY_MANAGER.with(|m| m.borrow().get(TWIN_LEDGER_REQUESTS))
 }
 
+const ADDRESS_TX_INDEX: MemoryId = MemoryId::new(8);
+
+pub fn address_tx_index_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(ADDRESS_TX_INDEX))
+}
+
+const PRINCIPAL_TX_INDEX: MemoryId = MemoryId::new(9);
+
+pub fn principal_tx_index_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(PRINCIPAL_TX_INDEX))
+}
+
+const ICP_TO_EVM_HASH_INDEX: MemoryId = MemoryId::new(10);
+
+pub fn icp_to_evm_hash_index_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(ICP_TO_EVM_HASH_INDEX))
+}
+
+const EVM_TO_ICP_MINT_INDEX: MemoryId = MemoryId::new(11);
+
+pub fn evm_to_icp_mint_index_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(EVM_TO_ICP_MINT_INDEX))
+}
+
+const SCRAPE_HEALTH: MemoryId = MemoryId::new(12);
+
+pub fn scrape_health_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(SCRAPE_HEALTH))
+}
+
+const DISABLED_MINTERS: MemoryId = MemoryId::new(13);
+
+pub fn disabled_minters_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(DISABLED_MINTERS))
+}
+
+const FAILED_SCRAPE_RANGES: MemoryId = MemoryId::new(14);
+
+pub fn failed_scrape_ranges_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(FAILED_SCRAPE_RANGES))
+}
+
+const SCRAPE_INTERVALS: MemoryId = MemoryId::new(15);
+
+pub fn scrape_intervals_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(SCRAPE_INTERVALS))
+}
+
+const TRANSACTION_TIMELINES: MemoryId = MemoryId::new(16);
+
+pub fn transaction_timelines_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(TRANSACTION_TIMELINES))
+}
+
+const TRANSACTION_ATTEMPTS: MemoryId = MemoryId::new(17);
+
+pub fn transaction_attempts_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(TRANSACTION_ATTEMPTS))
+}
+
+const SYNCED_BLOCKS: MemoryId = MemoryId::new(18);
+
+pub fn synced_blocks_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(SYNCED_BLOCKS))
+}
+
+const SKIPPED_BLOCKS: MemoryId = MemoryId::new(19);
+
+pub fn skipped_blocks_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(SKIPPED_BLOCKS))
+}
+
+const DEPOSIT_LATENCIES: MemoryId = MemoryId::new(20);
+
+pub fn deposit_latencies_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(DEPOSIT_LATENCIES))
+}
+
+const BRIDGE_STATS: MemoryId = MemoryId::new(21);
+
+pub fn bridge_stats_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(BRIDGE_STATS))
+}
+
+const TRANSFER_USD_VALUES: MemoryId = MemoryId::new(22);
+
+pub fn transfer_usd_values_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(TRANSFER_USD_VALUES))
+}
+
+const USD_VOLUMES: MemoryId = MemoryId::new(23);
+
+pub fn usd_volumes_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(USD_VOLUMES))
+}
+
+const HOURLY_TOKEN_PRICES: MemoryId = MemoryId::new(24);
+
+pub fn hourly_token_prices_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(HOURLY_TOKEN_PRICES))
+}
+
+const DAILY_TOKEN_PRICES: MemoryId = MemoryId::new(25);
+
+pub fn daily_token_prices_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(DAILY_TOKEN_PRICES))
+}
+
+const EVM_TOKEN_PRICES: MemoryId = MemoryId::new(26);
+
+pub fn evm_token_prices_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(EVM_TOKEN_PRICES))
+}
+
+const EVM_TOKEN_EDITS: MemoryId = MemoryId::new(27);
+
+pub fn evm_token_edits_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(EVM_TOKEN_EDITS))
+}
+
+const PENDING_INDEX_BACKFILL: MemoryId = MemoryId::new(28);
+
+pub fn pending_index_backfill_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(PENDING_INDEX_BACKFILL))
+}
+
+const TRANSACTION_TIME_INDEX: MemoryId = MemoryId::new(29);
+
+pub fn transaction_time_index_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(TRANSACTION_TIME_INDEX))
+}
+
+const TRANSACTION_STATUS_COUNTS: MemoryId = MemoryId::new(30);
+
+pub fn transaction_status_counts_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(TRANSACTION_STATUS_COUNTS))
+}
+
+const REIMBURSEMENTS: MemoryId = MemoryId::new(31);
+
+pub fn reimbursements_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(REIMBURSEMENTS))
+}
+
+const OPEN_TRANSFERS: MemoryId = MemoryId::new(32);
+
+pub fn open_transfers_memory() -> StableMemory {
+    MEMORY_MANAGER.with(|m| m.borrow().get(OPEN_TRANSFERS))
+}
+
+// Every virtual memory in use, labelled for the stable memory metrics
+const STABLE_MEMORIES: [(MemoryId, &str); 33] = [
+    (MINTERS_MEMORY_ID, "minters"),
+    (EVM_TO_ICP_MEMORY_ID, "evm_to_icp_txs"),
+    (ICP_TO_EVM_MEMORY_ID, "icp_to_evm_txs"),
+    (SUPPORTED_CK_MEMORY_ID, "supported_ckerc20_tokens"),
+    (SUPPORTED_APPIC_MEMORY_ID, "supported_twin_appic_tokens"),
+    (EVM_TOKEN_LIST, "evm_token_list"),
+    (ICP_TOKEN_LIST, "icp_token_list"),
+    (TWIN_LEDGER_REQUESTS, "twin_erc20_requests"),
+    (ADDRESS_TX_INDEX, "address_tx_index"),
+    (PRINCIPAL_TX_INDEX, "principal_tx_index"),
+    (ICP_TO_EVM_HASH_INDEX, "icp_to_evm_hash_index"),
+    (EVM_TO_ICP_MINT_INDEX, "evm_to_icp_mint_index"),
+    (SCRAPE_HEALTH, "scrape_health"),
+    (DISABLED_MINTERS, "disabled_minters"),
+    (FAILED_SCRAPE_RANGES, "failed_scrape_ranges"),
+    (SCRAPE_INTERVALS, "scrape_intervals"),
+    (TRANSACTION_TIMELINES, "transaction_timelines"),
+    (TRANSACTION_ATTEMPTS, "transaction_attempts"),
+    (SYNCED_BLOCKS, "synced_blocks"),
+    (SKIPPED_BLOCKS, "skipped_blocks"),
+    (DEPOSIT_LATENCIES, "deposit_latencies"),
+    (BRIDGE_STATS, "bridge_stats"),
+    (TRANSFER_USD_VALUES, "transfer_usd_values"),
+    (USD_VOLUMES, "usd_volumes"),
+    (HOURLY_TOKEN_PRICES, "hourly_token_prices"),
+    (DAILY_TOKEN_PRICES, "daily_token_prices"),
+    (EVM_TOKEN_PRICES, "evm_token_prices"),
+    (EVM_TOKEN_EDITS, "evm_token_edits"),
+    (PENDING_INDEX_BACKFILL, "pending_index_backfill"),
+    (TRANSACTION_TIME_INDEX, "transaction_time_index"),
+    (TRANSACTION_STATUS_COUNTS, "transaction_status_counts"),
+    (REIMBURSEMENTS, "reimbursements"),
+    (OPEN_TRANSFERS, "open_transfers"),
+];
+
+// Size in wasm pages of every virtual memory
+pub fn stable_memory_sizes() -> Vec<(&'static str, u64)> {
+    MEMORY_MANAGER.with(|m| {
+        let manager = m.borrow();
+        STABLE_MEMORIES
+            .iter()
+            .map(|(memory_id, name)| (*name, manager.get(*memory_id).size()))
+            .collect()
+    })
+}
+
 impl Storable for MinterKey {
     fn to_bytes(&self) -> Cow<[u8]> {
         encode(self)
@@ -67,6 +267,258 @@ impl Storable for MinterKey {
     const BOUND: Bound = Bound::Unbounded;
 }
 
+impl Storable for ScrapeHealth {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for FailedScrapeRangeKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TimelineKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TimelineEntry {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TimelineStatus {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for SyncedBlocks {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for BridgeStatsKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for BridgeStatsBucket {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TransactionCursor {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TransactionKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TransferUsdValue {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for UsdVolumeBucket {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for EvmTokenEdit {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for EvmTokenPrice {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for UsdPrice {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TokenPriceKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for DepositLatencies {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for SkippedBlockKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TransactionAttemptKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for TransactionAttempt {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for FailedScrapeRange {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
 impl Storable for Minter {
     fn to_bytes(&self) -> Cow<[u8]> {
         encode(self)
@@ -151,6 +603,18 @@ impl Storable for IcpToEvmTx {
     const BOUND: Bound = Bound::Unbounded;
 }
 
+impl Storable for Reimbursement {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
 impl Storable for Erc20Identifier {
     fn to_bytes(&self) -> Cow<[u8]> {
         encode(self)
@@ -247,6 +711,45 @@ impl Storable for Erc20TwinLedgerSuiteFee {
     const BOUND: Bound = Bound::Unbounded;
 }
 
+impl Storable for TxHashKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for MintIndexKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl<Account> Storable for TxIndexKey<Account>
+where
+    Account: Serialize + for<'a> Deserialize<'a>,
+{
+    fn to_bytes(&self) -> Cow<[u8]> {
+        encode(self)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode(bytes)
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
 fn encode<T: ?Sized + serde::Serialize>(value: &T) -> Cow<[u8]> {
     let bytes = bincode::serialize(value).expect("failed to encode");
     Cow::Owned(bytes)
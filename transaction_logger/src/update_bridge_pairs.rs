@@ -1,3 +1,6 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
 use candid::Principal;
 use ic_canister_log::log;
 
@@ -5,21 +8,101 @@ use crate::{
     guard::TimerGuard,
     ledger_manager_client::LsClient,
     logs::{DEBUG, INFO},
-    state::{mutate_state, BridgePair},
+    minter_clinet::{IcRunTime, Runtime},
+    state::{mutate_state, BridgePair, Erc20Identifier},
 };
 
 pub const LEDGER_SUITE_ORCHESTRATOR_ID: &str = "vxkom-oyaaa-aaaar-qafda-cai";
 pub const APPIC_LEDGER_MANAGER_ID: &str = "kmcdp-4yaaa-aaaag-ats3q-cai";
 
+thread_local! {
+    // Refresh requested through request_update_bridge_pairs that has not run yet, requests made
+    // before it runs are merged into it
+    static REQUESTED_REFRESH: RefCell<Option<BridgePairsRefresh>> = RefCell::default();
+}
+
+// Bridge pairs a run of update_bridge_pairs records
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BridgePairsRefresh {
+    All,
+    Tokens(BTreeSet<Erc20Identifier>),
+}
+
+impl BridgePairsRefresh {
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Tokens(mut tokens), Self::Tokens(other_tokens)) => {
+                tokens.extend(other_tokens);
+                Self::Tokens(tokens)
+            }
+            _ => Self::All,
+        }
+    }
+
+    fn includes(&self, erc20_identifier: &Erc20Identifier) -> bool {
+        match self {
+            Self::All => true,
+            Self::Tokens(tokens) => tokens.contains(erc20_identifier),
+        }
+    }
+}
+
+// Returns true if no run was pending, so the caller has to schedule one
+pub fn request_bridge_pairs_refresh(refresh: BridgePairsRefresh) -> bool {
+    REQUESTED_REFRESH.with(|requested| {
+        let mut requested = requested.borrow_mut();
+        let schedule = requested.is_none();
+        *requested = Some(match requested.take() {
+            Some(pending) => pending.merge(refresh),
+            None => refresh,
+        });
+        schedule
+    })
+}
+
 /// Checks twin tokens supported by ledger_suite_orchestrator and ledger_suite_manager on an interval basis.
 /// If there are new twin tokens, they are added to the state.
 pub async fn update_bridge_pairs() {
+    update_bridge_pairs_with_runtime(IcRunTime()).await
+}
+
+pub async fn update_bridge_pairs_with_runtime<R: Runtime + Clone>(runtime: R) {
     // Issue a timer gaurd
     let _gaurd = match TimerGuard::new(crate::guard::TaskType::UpdateBridgePairs) {
         Ok(gaurd) => gaurd,
         Err(_) => return,
     };
 
+    refresh_bridge_pairs(runtime, &BridgePairsRefresh::All).await
+}
+
+// Runs the refresh requested through request_update_bridge_pairs
+// Returns true if another run has to be scheduled, which happens when an update was already
+// running and the request was put back
+pub async fn update_requested_bridge_pairs() -> bool {
+    update_requested_bridge_pairs_with_runtime(IcRunTime()).await
+}
+
+pub async fn update_requested_bridge_pairs_with_runtime<R: Runtime + Clone>(runtime: R) -> bool {
+    let Some(refresh) = REQUESTED_REFRESH.with(|requested| requested.borrow_mut().take()) else {
+        return false;
+    };
+
+    let _gaurd = match TimerGuard::new(crate::guard::TaskType::UpdateBridgePairs) {
+        Ok(gaurd) => gaurd,
+        Err(_) => return request_bridge_pairs_refresh(refresh),
+    };
+
+    log!(
+        INFO,
+        "[Scrape new twin tokens] Running requested refresh of {:?}",
+        refresh
+    );
+    refresh_bridge_pairs(runtime, &refresh).await;
+    false
+}
+
+async fn refresh_bridge_pairs<R: Runtime + Clone>(runtime: R, refresh: &BridgePairsRefresh) {
     let managers = [
         (
             APPIC_LEDGER_MANAGER_ID,
@@ -34,7 +117,11 @@ pub async fn update_bridge_pairs() {
     ];
 
     for (manager_id, operator, source_name) in managers {
-        let client = LsClient::new(Principal::from_text(manager_id).unwrap(), operator.clone());
+        let client = LsClient::with_runtime(
+            runtime.clone(),
+            Principal::from_text(manager_id).unwrap(),
+            operator.clone(),
+        );
 
         log!(
             INFO,
@@ -43,9 +130,13 @@ pub async fn update_bridge_pairs() {
         );
 
         match client.get_erc20_list().await {
-            Ok(bridge_pairs) => {
-                process_bridge_pairs(bridge_pairs.get_bridge_pairs_iter(), operator, source_name)
-            }
+            Ok(bridge_pairs) => process_bridge_pairs(
+                bridge_pairs
+                    .get_bridge_pairs_iter()
+                    .filter(|(erc20_identifier, _ledger_id)| refresh.includes(erc20_identifier)),
+                operator,
+                source_name,
+            ),
             Err(err) => {
                 log!(
                     DEBUG,
@@ -100,3 +191,174 @@ where
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger_manager_client::lsm_types::{
+        CyclesManagement, Erc20Contract, LedgerManagerInfo, ManagedCanisterStatus, ManagedCanisters,
+    };
+    use crate::minter_clinet::{mock_runtime::MockRuntime, Reason};
+    use crate::numeric::{Erc20TokenAmount, UsdPrice};
+    use crate::state::{
+        read_state, ChainId, Erc20Identifier, EvmToken, IcpToken, IcpTokenType, Operator,
+    };
+    use candid::Nat;
+    use futures::executor::block_on;
+    use ic_ethereum_types::Address;
+    use std::str::FromStr;
+
+    const USDT_ADDRESS: &str = "0x55d398326f99059fF775485246999027B3197955";
+
+    fn lsm_info(ledger_id: Principal) -> LedgerManagerInfo {
+        LedgerManagerInfo {
+            managed_canisters: vec![ManagedCanisters {
+                erc20_contract: Erc20Contract {
+                    chain_id: Nat::from(56_u64),
+                    address: USDT_ADDRESS.to_string(),
+                },
+                twin_erc20_token_symbol: "icUSDT.bsc".to_string(),
+                ledger: Some(ManagedCanisterStatus::Installed {
+                    canister_id: ledger_id,
+                    installed_wasm_hash: "".to_string(),
+                }),
+                index: None,
+                archives: vec![],
+            }],
+            cycles_management: CyclesManagement {
+                cycles_for_ledger_creation: Nat::from(0_u64),
+                cycles_for_archive_creation: Nat::from(0_u64),
+                cycles_for_index_creation: Nat::from(0_u64),
+                cycles_top_up_increment: Nat::from(0_u64),
+            },
+            more_controller_ids: vec![],
+            minter_ids: vec![],
+            ledger_suite_version: None,
+            ls_creation_icp_fee: Nat::from(0_u64),
+            ls_creation_appic_fee: None,
+        }
+    }
+
+    fn record_known_tokens(erc20_identifier: &Erc20Identifier, ledger_id: Principal) {
+        mutate_state(|s| {
+            s.record_evm_token(
+                erc20_identifier.clone(),
+                EvmToken {
+                    chain_id: ChainId(56),
+                    erc20_contract_address: erc20_identifier.0,
+                    name: "Tether USD".to_string(),
+                    decimals: 18,
+                    symbol: "USDT".to_string(),
+                    logo: "".to_string(),
+                },
+            );
+            s.record_icp_token(
+                ledger_id,
+                IcpToken {
+                    ledger_id,
+                    name: "icUSDT.bsc".to_string(),
+                    decimals: 18,
+                    symbol: "icUSDT.bsc".to_string(),
+                    usd_price: UsdPrice::ZERO,
+                    logo: "".to_string(),
+                    fee: Erc20TokenAmount::from(10_u64),
+                    token_type: IcpTokenType::ICRC2,
+                    rank: None,
+                },
+            );
+        });
+    }
+
+    #[test]
+    fn should_record_bridge_pairs_of_known_tokens() {
+        let erc20_identifier =
+            Erc20Identifier(Address::from_str(USDT_ADDRESS).unwrap(), ChainId(56));
+        let ledger_id = Principal::from_slice(&[7]);
+
+        record_known_tokens(&erc20_identifier, ledger_id);
+
+        let runtime = MockRuntime::new();
+        runtime.add_response(
+            Principal::from_text(APPIC_LEDGER_MANAGER_ID).unwrap(),
+            "get_lsm_info",
+            lsm_info(ledger_id),
+        );
+        // A failing orchestrator should not prevent the Appic pairs from being recorded
+        runtime.add_error(
+            Principal::from_text(LEDGER_SUITE_ORCHESTRATOR_ID).unwrap(),
+            "get_orchestrator_info",
+            Reason::CanisterError("trapped".to_string()),
+        );
+
+        block_on(update_bridge_pairs_with_runtime(runtime.clone()));
+
+        assert_eq!(runtime.calls().len(), 2);
+        assert_eq!(
+            read_state(|s| s.get_icrc_twin_for_erc20(&erc20_identifier, &Operator::AppicMinter)),
+            Some(ledger_id)
+        );
+        assert_eq!(
+            read_state(
+                |s| s.get_icrc_twin_for_erc20(&erc20_identifier, &Operator::DfinityCkEthMinter)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn should_refresh_only_requested_bridge_pairs_once_no_update_is_running() {
+        let erc20_identifier =
+            Erc20Identifier(Address::from_str(USDT_ADDRESS).unwrap(), ChainId(56));
+        let other_identifier = Erc20Identifier(Address::ZERO, ChainId(56));
+        let ledger_id = Principal::from_slice(&[7]);
+        record_known_tokens(&erc20_identifier, ledger_id);
+
+        let runtime = MockRuntime::new();
+        for _ in 0..2 {
+            runtime.add_response(
+                Principal::from_text(APPIC_LEDGER_MANAGER_ID).unwrap(),
+                "get_lsm_info",
+                lsm_info(ledger_id),
+            );
+            runtime.add_error(
+                Principal::from_text(LEDGER_SUITE_ORCHESTRATOR_ID).unwrap(),
+                "get_orchestrator_info",
+                Reason::CanisterError("trapped".to_string()),
+            );
+        }
+        let appic_twin =
+            || read_state(|s| s.get_icrc_twin_for_erc20(&erc20_identifier, &Operator::AppicMinter));
+
+        // Only the first request schedules a run
+        assert!(request_bridge_pairs_refresh(BridgePairsRefresh::Tokens(
+            BTreeSet::from([other_identifier.clone()])
+        )));
+        assert!(!request_bridge_pairs_refresh(BridgePairsRefresh::Tokens(
+            BTreeSet::from([other_identifier])
+        )));
+        assert!(!block_on(update_requested_bridge_pairs_with_runtime(
+            runtime.clone()
+        )));
+        assert_eq!(appic_twin(), None);
+
+        // A request made while an update is running is put back
+        assert!(request_bridge_pairs_refresh(BridgePairsRefresh::Tokens(
+            BTreeSet::from([erc20_identifier.clone()])
+        )));
+        let guard = TimerGuard::new(crate::guard::TaskType::UpdateBridgePairs).unwrap();
+        assert!(block_on(update_requested_bridge_pairs_with_runtime(
+            runtime.clone()
+        )));
+        drop(guard);
+        assert!(!block_on(update_requested_bridge_pairs_with_runtime(
+            runtime.clone()
+        )));
+
+        assert_eq!(appic_twin(), Some(ledger_id));
+        assert_eq!(runtime.pending_responses(), 0);
+        // Nothing is left to run
+        assert!(!block_on(update_requested_bridge_pairs_with_runtime(
+            runtime
+        )));
+    }
+}
@@ -1,17 +1,24 @@
 use crate::{
     guard::TimerGuard,
-    icp_tokens_service::TokenService,
+    icp_tokens_service::{
+        price_oracle::{aggregate_usd_prices, PRICE_SOURCES},
+        TokenService,
+    },
     logs::INFO,
+    minter_clinet::{IcRunTime, Runtime},
     state::{mutate_state, read_state, IcpToken},
 };
-use candid::Principal;
 use futures::future::join_all;
 use ic_canister_log::log;
-use std::{collections::HashSet, str::FromStr};
+use std::collections::HashSet;
 
 const VALIDATION_BATCH_SIZE: usize = 5;
 
 pub async fn update_icp_tokens() {
+    update_icp_tokens_with_runtime(IcRunTime()).await
+}
+
+pub async fn update_icp_tokens_with_runtime<R: Runtime>(runtime: R) {
     // Issue a timer gaurd
     let _gaurd = match TimerGuard::new(crate::guard::TaskType::UpdateIcpTokens) {
         Ok(gaurd) => gaurd,
@@ -25,7 +32,7 @@ pub async fn update_icp_tokens() {
         Err(_) => return,
     };
 
-    let token_service = TokenService::new();
+    let token_service = TokenService::with_runtime(runtime);
 
     // Fetch tokens concurrently
     let (icp_swap_tokens, sonic_swap_tokens) = (
@@ -75,40 +82,67 @@ pub async fn update_icp_tokens() {
 
 // Runs Intervaly to update usd price of icp tokens
 pub async fn update_usd_price() {
+    update_usd_price_with_runtime(IcRunTime()).await
+}
+
+pub async fn update_usd_price_with_runtime<R: Runtime>(runtime: R) {
     let _gaurd = match TimerGuard::new(crate::guard::TaskType::UpdateUsdPrice) {
         Ok(gaurd) => gaurd,
         Err(_) => return,
     };
 
-    let token_service = TokenService::new();
+    let now = runtime.time();
+    let token_service = TokenService::with_runtime(runtime);
+    let tokens = read_state(|s| s.get_icp_tokens());
 
-    let icp_token_with_usd_price = token_service
-        .get_icp_swap_tokens_with_usd_price()
-        .await
-        .expect("Failed to get icp tokens with their price, will retry in next iteration");
+    // Query every source concurrently, a failing source only loses its own quotes
+    let results = join_all(
+        PRICE_SOURCES
+            .iter()
+            .map(|source| token_service.get_usd_price_quotes(*source, &tokens, now)),
+    )
+    .await;
+
+    let mut quotes = vec![];
+    for (source, result) in PRICE_SOURCES.iter().zip(results) {
+        match result {
+            Ok(source_quotes) => quotes.extend(source_quotes),
+            Err(e) => log!(
+                INFO,
+                "[Update Usd Price] Failed to get quotes from {:?}, will retry in next iteration: {}",
+                source,
+                e
+            ),
+        }
+    }
 
-    icp_token_with_usd_price
-        .iter()
-        .filter(|token| token.priceUSD != 0_f64 && token.volumeUSD7d != 0_f64)
-        .for_each(|token| {
-            mutate_state(|s| {
-                s.update_icp_token_usd_price(
-                    Principal::from_str(&token.address).unwrap_or(Principal::anonymous()),
-                    token.priceUSD.to_string(),
-                );
-            })
-        });
+    let usd_prices = aggregate_usd_prices(quotes, now);
+
+    log!(
+        INFO,
+        "[Update Usd Price] Updating usd price of {} tokens",
+        usd_prices.len()
+    );
+    mutate_state(|s| {
+        for (ledger_id, usd_price) in usd_prices {
+            s.update_icp_token_usd_price(ledger_id, usd_price, now);
+        }
+    });
 }
 
 // Runs intervaly to remove invalid tokens
 pub async fn validate_tokens() {
+    validate_tokens_with_runtime(IcRunTime()).await
+}
+
+pub async fn validate_tokens_with_runtime<R: Runtime>(runtime: R) {
     // Issue a timer gaurd
     let _gaurd = match TimerGuard::new(crate::guard::TaskType::RemoveInvalidTokens) {
         Ok(gaurd) => gaurd,
         Err(_) => return,
     };
 
-    let tokens_service = TokenService::new();
+    let tokens_service = TokenService::with_runtime(runtime);
 
     // Get all tokens from state
     let tokens = read_state(|s| s.get_icp_tokens());
@@ -141,10 +175,10 @@ pub async fn validate_tokens() {
     );
 }
 
-async fn validate_tokens_in_batch<'a>(
+async fn validate_tokens_in_batch<'a, R: Runtime>(
     icp_tokens: &'a [IcpToken], // Borrow tokens as a slice to avoid ownership transfer
     batch_size: usize,
-    token_service: &TokenService,
+    token_service: &TokenService<R>,
 ) -> Vec<&'a IcpToken> {
     // Return references to the valid tokens
     let mut valid_tokens = Vec::new();
@@ -191,7 +225,7 @@ async fn validate_tokens_in_batch<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::numeric::Erc20TokenAmount;
+    use crate::numeric::{Erc20TokenAmount, UsdPrice};
     use crate::state::IcpToken;
     use crate::state::IcpTokenType;
 
@@ -208,7 +242,7 @@ mod tests {
             token_type: IcpTokenType::ICRC2,
             fee: Erc20TokenAmount::from(500_u64),
             rank: Some(1),
-            usd_price: "0".to_string(),
+            usd_price: UsdPrice::ZERO,
             logo: "".to_string(),
         };
 
@@ -220,7 +254,7 @@ mod tests {
             token_type: IcpTokenType::DIP20,
             fee: Erc20TokenAmount::from(500_u64),
             rank: None,
-            usd_price: "0".to_string(),
+            usd_price: UsdPrice::ZERO,
             logo: "".to_string(),
         };
 
@@ -232,7 +266,7 @@ mod tests {
             token_type: IcpTokenType::Other("Custom".into()),
             fee: Erc20TokenAmount::from(500_u64),
             rank: Some(2),
-            usd_price: "0".to_string(),
+            usd_price: UsdPrice::ZERO,
             logo: "".to_string(),
         };
 
@@ -251,7 +285,7 @@ mod tests {
                 token_type: IcpTokenType::ICRC1,
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: Some(3),
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
             IcpToken {
@@ -262,7 +296,7 @@ mod tests {
                 token_type: IcpTokenType::DIP20,
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: Some(2),
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
         ];
@@ -276,7 +310,7 @@ mod tests {
                 token_type: IcpTokenType::DIP20,
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: None,
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
             IcpToken {
@@ -287,7 +321,7 @@ mod tests {
                 token_type: IcpTokenType::Other("Custom".into()),
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: Some(1),
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
         ];
@@ -318,7 +352,7 @@ mod tests {
                 token_type: IcpTokenType::ICRC1,
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: Some(2),
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
             IcpToken {
@@ -329,7 +363,7 @@ mod tests {
                 token_type: IcpTokenType::ICRC2,
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: None,
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
             IcpToken {
@@ -340,7 +374,7 @@ mod tests {
                 token_type: IcpTokenType::DIP20,
                 fee: Erc20TokenAmount::from(500_u64),
                 rank: Some(2),
-                usd_price: "0".to_string(),
+                usd_price: UsdPrice::ZERO,
                 logo: "".to_string(),
             },
         ];
@@ -348,4 +382,184 @@ mod tests {
         let unique: HashSet<_> = tokens.into_iter().collect();
         assert_eq!(unique.len(), 2); // Only two unique tokens based on ledger_id
     }
+
+    #[test]
+    fn should_record_only_validated_tokens() {
+        use crate::icp_tokens_service::{
+            icp_swap_token_type::{TokenMetadata, TokensListResult},
+            sonic_swap_types::TokenInfoWithType,
+            ICP_SWAP_ID, SONIC_ID,
+        };
+        use crate::minter_clinet::mock_runtime::MockRuntime;
+        use candid::Nat;
+        use futures::executor::block_on;
+
+        let icp_swap_ledger = Principal::from_text("5573k-xaaaa-aaaak-aacnq-cai").unwrap();
+        let sonic_ledger = Principal::from_text("dikjh-xaaaa-aaaak-afnba-cai").unwrap();
+        let dead_ledger = Principal::from_text("sr5fw-zqaaa-aaaak-qig5q-cai").unwrap();
+
+        let icp_swap_token = |ledger_id: Principal, symbol: &str| TokenMetadata {
+            fee: Nat::from(10_u64),
+            configs: vec![],
+            decimals: Nat::from(8_u64),
+            name: symbol.to_string(),
+            rank: 1,
+            media_links: vec![],
+            total_supply: Nat::from(1_000_u64),
+            introduction: "".to_string(),
+            standard: "ICRC2".to_string(),
+            symbol: symbol.to_string(),
+            canister_id: ledger_id.to_string(),
+        };
+
+        let runtime = MockRuntime::new();
+        runtime.add_response(
+            Principal::from_text(ICP_SWAP_ID).unwrap(),
+            "getList",
+            TokensListResult::Ok(vec![
+                icp_swap_token(icp_swap_ledger, "TKA"),
+                icp_swap_token(dead_ledger, "DEAD"),
+            ]),
+        );
+        runtime.add_response(
+            Principal::from_text(SONIC_ID).unwrap(),
+            "getSupportedTokenList",
+            vec![TokenInfoWithType {
+                id: sonic_ledger.to_string(),
+                fee: Nat::from(10_u64),
+                decimals: 6,
+                name: "TokenB".to_string(),
+                total_supply: Nat::from(1_000_u64),
+                block_status: "".to_string(),
+                token_type: "ICRC1".to_string(),
+                symbol: "TKB".to_string(),
+            }],
+        );
+        runtime.add_response(icp_swap_ledger, "icrc1_decimals", 8_u8);
+        runtime.add_response(sonic_ledger, "icrc1_decimals", 6_u8);
+        // No response for dead_ledger, so both validation attempts fail
+
+        block_on(update_icp_tokens_with_runtime(runtime.clone()));
+
+        assert_eq!(runtime.pending_responses(), 0);
+        assert!(read_state(|s| s.get_icp_token_by_principal(&icp_swap_ledger)).is_some());
+        assert!(read_state(|s| s.get_icp_token_by_principal(&sonic_ledger)).is_some());
+        assert!(read_state(|s| s.get_icp_token_by_principal(&dead_ledger)).is_none());
+    }
+
+    #[test]
+    fn should_update_usd_prices_when_a_source_fails() {
+        use crate::icp_tokens_service::{
+            kong_swap_types::{PoolReply, PoolsResult},
+            sonic_swap_types::PairInfoExt,
+            ICP_SWAP_NODE, KONG_SWAP_ID, SONIC_ID, XRC_ASSETS, XRC_ID,
+        };
+        use crate::minter_clinet::{mock_runtime::MockRuntime, Reason};
+        use crate::numeric::{UsdPrice, USD_DECIMALS};
+        use candid::Nat;
+        use futures::executor::block_on;
+
+        const NOW: u64 = 1_700_000_000_000_000_000;
+        let ckusdc = "xevnm-gaaaa-aaaar-qafnq-cai";
+        let ckusdt = "cngnf-vqaaa-aaaar-qag4q-cai";
+        let token_a = Principal::from_text("5573k-xaaaa-aaaak-aacnq-cai").unwrap();
+        let token_b = Principal::from_text("dikjh-xaaaa-aaaak-afnba-cai").unwrap();
+
+        mutate_state(|s| {
+            for ledger_id in [token_a, token_b] {
+                s.record_icp_token(
+                    ledger_id,
+                    IcpToken {
+                        ledger_id,
+                        name: "Token".to_string(),
+                        decimals: 8,
+                        symbol: "TK".to_string(),
+                        token_type: IcpTokenType::ICRC2,
+                        fee: Erc20TokenAmount::from(10_u64),
+                        rank: None,
+                        usd_price: UsdPrice::ZERO,
+                        logo: "".to_string(),
+                    },
+                )
+            }
+        });
+
+        let kong_pool = |address_0: &str, address_1: &str, price: f64| PoolReply {
+            pool_id: 1,
+            name: "".to_string(),
+            symbol: "".to_string(),
+            chain_0: "IC".to_string(),
+            symbol_0: "".to_string(),
+            address_0: address_0.to_string(),
+            balance_0: Nat::from(0_u8),
+            chain_1: "IC".to_string(),
+            symbol_1: "".to_string(),
+            address_1: address_1.to_string(),
+            balance_1: Nat::from(0_u8),
+            price,
+            is_removed: false,
+        };
+
+        let sonic_pair =
+            |token: Principal, stable_reserve: u64, block_timestamp: u64| PairInfoExt {
+                id: "".to_string(),
+                price0CumulativeLast: Nat::from(0_u8),
+                creator: Principal::anonymous(),
+                reserve0: Nat::from(100_000_000_000_u64),
+                reserve1: Nat::from(stable_reserve),
+                lptoken: "".to_string(),
+                totalSupply: Nat::from(0_u8),
+                token0: token.to_string(),
+                token1: ckusdt.to_string(),
+                price1CumulativeLast: Nat::from(0_u8),
+                kLast: Nat::from(0_u8),
+                blockTimestampLast: candid::Int::from(block_timestamp),
+            };
+
+        let runtime = MockRuntime::new();
+        runtime.set_time(NOW);
+        runtime.add_error(
+            Principal::from_text(ICP_SWAP_NODE).unwrap(),
+            "getAllTokens",
+            Reason::Rejected("out of cycles".to_string()),
+        );
+        // 1_000 token_a against 2_400 ckUSDT, and a pool of token_b that did not trade for a day
+        // and is too stale to be used
+        runtime.add_response(
+            Principal::from_text(SONIC_ID).unwrap(),
+            "getAllPairs",
+            vec![
+                sonic_pair(token_a, 2_400_000_000, NOW - 60 * 1_000_000_000),
+                sonic_pair(token_b, 2_200_000_000, NOW - 24 * 60 * 60 * 1_000_000_000),
+            ],
+        );
+        runtime.add_response(
+            Principal::from_text(KONG_SWAP_ID).unwrap(),
+            "pools",
+            PoolsResult::Ok(vec![
+                kong_pool(&token_a.to_string(), ckusdt, 2.5),
+                kong_pool(ckusdc, &token_b.to_string(), 0.5),
+            ]),
+        );
+        // The exchange rate canister rejects every call
+        for _ in XRC_ASSETS {
+            runtime.add_error(
+                Principal::from_text(XRC_ID).unwrap(),
+                "get_exchange_rate",
+                Reason::Rejected("out of cycles".to_string()),
+            );
+        }
+
+        block_on(update_usd_price_with_runtime(runtime.clone()));
+
+        assert_eq!(runtime.pending_responses(), 0);
+        assert_eq!(
+            read_state(|s| s.get_icp_token_price(&token_a)),
+            Some(UsdPrice::from_decimal_str("2.45", USD_DECIMALS).unwrap())
+        );
+        assert_eq!(
+            read_state(|s| s.get_icp_token_price(&token_b)),
+            Some(UsdPrice::from_decimal_str("2", USD_DECIMALS).unwrap())
+        );
+    }
 }